@@ -43,7 +43,7 @@ mod presets {
 	#[serde(default)]
 	pub struct DockerServicePreset {
 		/// The list of extended presets.
-		#[serde(skip_serializing)]
+		#[serde(skip_serializing_if = "IndexSet::is_empty")]
 		#[merge(skip)]
 		pub extends_presets: IndexSet<String>,
 
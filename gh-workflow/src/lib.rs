@@ -64,7 +64,6 @@ mod presets {
 	#[serde(untagged)]
 	pub enum StepPresetRef {
 		/// A preset ID
-		#[serde(skip_serializing)]
 		PresetId(String),
 		Preset(Box<Step>),
 	}
@@ -118,7 +117,7 @@ mod presets {
 	#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 	pub struct GhJobPreset {
 		/// The list of extended presets.
-		#[serde(skip_serializing, default)]
+		#[serde(skip_serializing_if = "IndexSet::is_empty", default)]
 		#[merge(skip)]
 		pub extends_presets: IndexSet<String>,
 
@@ -14,8 +14,30 @@ use rust_cmds::*;
 mod exec_cmd;
 use exec_cmd::*;
 
+mod config_cmds;
+use config_cmds::*;
+
+mod preset_cmds;
+use preset_cmds::*;
+
+mod regeneration;
+use regeneration::*;
+
+mod update_cmd;
+
+mod check_cmd;
+use check_cmd::*;
+
+mod import_cmd;
+
+mod completions;
+pub use completions::CompletionShell;
+use completions::*;
+
 pub(crate) mod parsers;
 
+use std::{future::Future, pin::Pin};
+
 use clap::Subcommand;
 
 use crate::{
@@ -34,6 +56,19 @@ pub async fn main_entrypoint() -> Result<(), AppError> {
 
 impl Cli {
 	async fn execute(self) -> Result<(), AppError> {
+		with_output_format(self.output_format, async {
+			let result = Box::pin(self.run()).await;
+
+			if let Err(e) = &result {
+				emit(OutputEvent::error(e));
+			}
+
+			result
+		})
+		.await
+	}
+
+	async fn run(self) -> Result<(), AppError> {
 		let mut config = get_config_from_cli(self.overrides.unwrap_or_default(), &self.command)?;
 
 		let command = self.command;
@@ -41,16 +76,99 @@ impl Cli {
 
 		for file in self.vars_files {
 			let vars = deserialize_vars_file(&file)?;
+			config
+				.sources
+				.add_vars(vars.keys(), &get_abs_path(&file)?);
 			config.vars.extend(vars);
 		}
 
-		let overwrite = config.can_overwrite();
-
 		if self.print_config {
-			println!("Full parsed config:");
-			println!("{config:?}");
+			let profile = config
+				.active_profile()
+				.map(|profile| format!("Active profile: {profile}\n"))
+				.unwrap_or_default();
+
+			let report = format!(
+				"{profile}Full parsed config:\n{config:?}\nSources:\n{}",
+				config.sources
+			);
+
+			// The json output only contains events
+			if is_json_output() {
+				eprint!("{report}");
+			} else {
+				print!("{report}");
+			}
 		}
 
+		let sources = config.sources.clone();
+
+		let review = ReviewOptions {
+			diff: self.review.diff,
+			confirm: self.review.confirm,
+		};
+
+		// Every generated file is compared with the file on disk, regardless of the overwrite settings
+		let overwrite_policies = if self.check {
+			config.no_overwrite = false;
+			OverwritePolicies::overwrite_all()
+		} else {
+			OverwritePolicies::from_config(&config)?
+		};
+
+		// The future is boxed, since it would be moved a few times on the stack otherwise.
+		// Its type is also erased, so that the compiler does not have to go through the whole chain of futures to check that it is `Send`
+		let command_future: Pin<Box<dyn Future<Output = AppResult> + Send>> =
+			Box::pin(Self::execute_command(command, config, cli_vars));
+
+		let future = with_review(
+			review,
+			with_overwrite_policies(overwrite_policies, with_transaction(command_future)),
+		);
+
+		let result = if self.dry_run {
+			let (result, dry_run) = with_dry_run(future).await;
+
+			// In the json output, the operations are already included in the events
+			if result.is_ok() && !is_json_output() {
+				for operation in dry_run.operations() {
+					println!("{operation}");
+				}
+			}
+
+			result
+		} else if self.check {
+			let (result, dry_run) = with_dry_run(future).await;
+
+			result.and_then(|()| check_generated_files(dry_run))
+		} else {
+			future.await
+		};
+
+		result.map_err(|e| sources.annotate(e))
+	}
+
+	pub(crate) async fn execute_command(
+		command: Commands,
+		config: Config,
+		cli_vars: IndexMap<String, Value>,
+	) -> AppResult {
+		let preset_id = command.preset_id().map(ToString::to_string);
+
+		with_written_files(with_current_preset(
+			preset_id.as_deref(),
+			Box::pin(Self::run_command(command, config, cli_vars)),
+		))
+		.await
+	}
+
+	async fn run_command(
+		command: Commands,
+		config: Config,
+		cli_vars: IndexMap<String, Value>,
+	) -> AppResult {
+		let overwrite = config.can_overwrite();
+
 		match command {
 			#[cfg(feature = "schemars")]
 			Commands::JsonSchema { output } => {
@@ -59,6 +177,67 @@ impl Cli {
 			Commands::Rust { command } => {
 				command.execute(&config)?;
 			}
+			Commands::Config { command } => {
+				command.execute(&config, &cli_vars)?;
+			}
+			Commands::Preset { command } => {
+				command.execute(&config)?;
+			}
+			Commands::Completions { shell } => {
+				print_completions(shell)?;
+			}
+			Commands::Import {
+				file,
+				id,
+				kind,
+				into,
+			} => {
+				Self::import_preset(&file, &id, kind, into)?;
+			}
+			Commands::List { kind } => {
+				if is_json_output() {
+					for kind in kind
+						.as_ref()
+						.map_or(PresetKind::value_variants(), std::slice::from_ref)
+					{
+						emit(OutputEvent::PresetList {
+							kind: *kind,
+							ids: config
+								.preset_ids(*kind)
+								.into_iter()
+								.map(ToString::to_string)
+								.collect(),
+						});
+					}
+				} else if let Some(kind) = kind {
+					for id in config.preset_ids(kind) {
+						println!("{id}");
+					}
+				} else {
+					for kind in PresetKind::value_variants() {
+						let ids = config.preset_ids(*kind);
+
+						if ids.is_empty() {
+							continue;
+						}
+
+						if let Some(name) = kind.to_possible_value() {
+							println!("{}:", name.get_name());
+						}
+
+						for id in ids {
+							println!("  {id}");
+						}
+					}
+				}
+			}
+			Commands::Show { kind, id } => {
+				let output = config.format_preset(kind, &id)?;
+
+				emit(OutputEvent::Output {
+					content: output.trim_end().to_string(),
+				});
+			}
 			Commands::Gitignore { preset, output } => {
 				let data = config.get_gitignore_preset(&preset)?;
 
@@ -171,21 +350,48 @@ impl Cli {
 				dir,
 				overrides,
 			} => {
-				let mut preset = if let Some(id) = preset {
-					config.get_repo_preset(&id)?
+				let mut repo_preset = if let Some(id) = &preset {
+					config.get_repo_preset(id)?
 				} else {
 					RepoPreset::default()
 				};
 
 				if let Some(overrides) = overrides {
-					preset.merge(overrides);
+					repo_preset.merge(overrides);
 				}
 
 				let out_dir = dir.unwrap_or_else(get_cwd);
 
 				create_all_dirs(&out_dir)?;
 
-				config.init_repo(preset, remote.as_deref(), &out_dir, &cli_vars)?;
+				let mut manifest = GenerationManifest::new(
+					&out_dir,
+					GenerationCommand::Repo { preset },
+					&cli_vars,
+				);
+
+				with_preset_policy_sync(repo_preset.overwrite, || {
+					config.init_repo(
+						repo_preset,
+						remote.as_deref(),
+						&out_dir,
+						&cli_vars,
+						&mut manifest,
+					)
+				})?;
+
+				manifest.write()?;
+			}
+
+			Commands::Check { dir } => {
+				let dir = dir.unwrap_or_else(get_cwd);
+
+				Self::check_project(&dir, config, cli_vars).await?;
+			}
+			Commands::Update { dir, reject } => {
+				let dir = dir.unwrap_or_else(get_cwd);
+
+				Self::update_project(&dir, config, cli_vars, reject).await?;
 			}
 
 			Commands::Render {
@@ -254,7 +460,10 @@ impl Cli {
 
 				let format = get_extension(&output_path)?.to_string_lossy();
 
-				let new_config = Config::default();
+				let new_config = Config {
+					version: Some(CONFIG_VERSION.to_string()),
+					..Default::default()
+				};
 
 				match format.as_ref() {
 					"yaml" => serialize_yaml(&new_config, &output_path, overwrite)?,
@@ -291,13 +500,37 @@ pub struct ConfigOverrides {
 	#[arg(long)]
 	pub no_overwrite: bool,
 
-	/// Sets a custom config file. Any file named `sketch.{yaml,json,toml}` in the cwd or in `XDG_CONFIG_HOME/sketch` will be detected automatically. If no file is found, the default settings are used
+	/// The policy for the files that already exist. It can be overridden by the presets and by the `overwrite_rules` in the config.
+	#[arg(long, value_name = "POLICY")]
+	pub overwrite: Option<OverwritePolicy>,
+
+	/// Never ask for the values of missing template variables, and exit with an error instead.
+	#[arg(long)]
+	pub no_input: bool,
+
+	/// Sets a custom config file. Any file named `sketch.{yaml,json,toml}` in the cwd, in its parent directories or in `XDG_CONFIG_HOME/sketch` will be detected automatically. If no file is found, the default settings are used
 	#[arg(short, long, value_name = "FILE")]
 	pub config: Option<PathBuf>,
 
 	/// Ignores any automatically detected config files, uses cli instructions and config file defined with --config.
 	#[arg(long)]
 	pub ignore_config: bool,
+
+	/// Applies a profile defined in the config on top of the rest of the config.
+	#[arg(long, env = "SKETCH_PROFILE", value_name = "NAME")]
+	pub profile: Option<String>,
+}
+
+/// The options to review the changes to existing files.
+#[derive(Args, Debug, Clone, Default)]
+pub struct ReviewArgs {
+	/// Prints a diff between each existing file and its new content before overwriting it.
+	#[arg(long)]
+	pub diff: bool,
+
+	/// Shows the diff for each existing file that would change, and asks whether the changes should be applied.
+	#[arg(long)]
+	pub confirm: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -308,6 +541,21 @@ pub struct Cli {
 	#[arg(long)]
 	pub print_config: bool,
 
+	/// Prints the directories and files that would be created or overwritten, and the shell commands that would run, without performing any of these operations.
+	#[arg(long)]
+	pub dry_run: bool,
+
+	/// Generates the files without writing them, and exits with an error if any of them is missing or different from the file on disk. Unlike the `check` command, it works with any command that generates files, and it does not need a generation manifest.
+	#[arg(long, conflicts_with_all = ["dry_run", "diff", "confirm"])]
+	pub check: bool,
+
+	#[command(flatten)]
+	pub review: ReviewArgs,
+
+	/// The format of the output. With `json`, each file that is created, overwritten or skipped, each shell command, each warning or error and the output of the commands that print presets or configs are printed as json objects on their own line.
+	#[arg(long, value_enum, default_value_t, value_name = "FORMAT")]
+	pub output_format: OutputFormat,
+
 	#[command(subcommand)]
 	pub command: Commands,
 
@@ -332,13 +580,71 @@ pub enum Commands {
 		output: Option<PathBuf>,
 	},
 
+	/// Commands to inspect and manage the configuration.
+	Config {
+		#[command(subcommand)]
+		command: ConfigCommands,
+	},
+
+	/// Commands to inspect and compare the presets.
+	Preset {
+		#[command(subcommand)]
+		command: PresetCommands,
+	},
+
+	/// Prints the script that enables the completions for a shell, including the ids of the presets defined in the config.
+	///
+	/// For example, for bash: `source <(sketch completions bash)`
+	Completions {
+		/// The target shell.
+		#[arg(value_enum)]
+		shell: CompletionShell,
+	},
+
+	/// Lists the ids of the available presets.
+	List {
+		/// Only lists the presets of this kind.
+		#[arg(value_enum)]
+		kind: Option<PresetKind>,
+	},
+
+	/// Prints a fully resolved preset, in the format of the file that it would generate.
+	Show {
+		/// The kind of preset.
+		#[arg(value_enum)]
+		kind: PresetKind,
+
+		/// The preset id.
+		id: String,
+	},
+
+	/// Turns an existing file into a preset, and adds it to a config file.
+	///
+	/// The file is validated with the type of its preset, and its content is added to the config while keeping the existing formatting and comments of the config.
+	Import {
+		/// The file to import, such as a `Cargo.toml` or a `package.json` file.
+		file: PathBuf,
+
+		/// The id of the new preset.
+		#[arg(long = "as", value_name = "ID")]
+		id: String,
+
+		/// The kind of preset. Detected from the name of the file if absent.
+		#[arg(short, long, value_enum)]
+		kind: Option<PresetKind>,
+
+		/// The config file where the preset should be added. [default: the config file in the cwd]
+		#[arg(long, value_name = "FILE")]
+		into: Option<PathBuf>,
+	},
+
 	/// Creates a new git repo from a preset.
 	Repo {
 		/// The directory where the new repo should be generated. [default: `.`]
 		dir: Option<PathBuf>,
 
 		/// Selects a git preset from a configuration file.
-		#[arg(short, long)]
+		#[arg(short, long, add = preset_candidates(PresetKind::Repo))]
 		preset: Option<String>,
 
 		#[command(flatten)]
@@ -349,6 +655,26 @@ pub enum Commands {
 		remote: Option<String>,
 	},
 
+	/// Generates the files of a project again, using the presets and variables recorded in its generation manifest.
+	///
+	/// The changes are merged with the local modifications. When both sides changed the same lines, conflict markers are added to the file.
+	Update {
+		/// The root of the project to update. [default: `.`]
+		dir: Option<PathBuf>,
+
+		/// Writes the changes that cannot be applied in a `.rej` file next to the target file, instead of adding conflict markers.
+		#[arg(long)]
+		reject: bool,
+	},
+
+	/// Generates the files of a project again without writing them, and reports the files that are missing, different from the generated version, or no longer generated by the presets.
+	///
+	/// Exits with an error if any file does not match the presets.
+	Check {
+		/// The root of the project to check. [default: `.`]
+		dir: Option<PathBuf>,
+	},
+
 	/// Renders a single template to a file or to stdout
 	Render {
 		/// The output path for the template/preset. Implies `stdout` if absent for single templates. Required when a preset is selected.
@@ -356,7 +682,7 @@ pub enum Commands {
 		output: Option<PathBuf>,
 
 		/// The id of a templating preset
-		#[arg(short, long, group = "input", requires = "output")]
+		#[arg(short, long, group = "input", requires = "output", add = preset_candidates(PresetKind::Templates))]
 		preset: Option<String>,
 
 		/// The path to the template file
@@ -381,6 +707,7 @@ pub enum Commands {
 	/// Generates a `.gitignore` file from a preset.
 	Gitignore {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::Gitignore))]
 		preset: String,
 
 		/// The output path of the new file [default: `.gitignore`]
@@ -393,7 +720,7 @@ pub enum Commands {
 		output: PathBuf,
 
 		/// The workflow preset ID. If extra job presets are specified, they are added in the given order.
-		#[arg(short, long)]
+		#[arg(short, long, add = preset_candidates(PresetKind::GithubWorkflow))]
 		preset: Option<String>,
 
 		/// The name for the output workflow. Overrides the name in the preset.
@@ -401,26 +728,28 @@ pub enum Commands {
 		name: Option<String>,
 
 		/// One or more IDs for job presets to add to the generated file.
-		#[arg(short, long)]
+		#[arg(short, long, add = preset_candidates(PresetKind::GithubWorkflowJob))]
 		jobs: Vec<String>,
 	},
 
 	/// Generates a Docker Compose file from a preset.
 	DockerCompose {
 		/// The preset id. Not required if services are added manually with the `--service` flag.
+		#[arg(add = preset_candidates(PresetKind::ComposeFile))]
 		preset: Option<String>,
 
 		/// The output path of the new file [default: `compose.yaml`]
 		output: Option<PathBuf>,
 
 		/// Adds one or many service presets to the generated file. Can specify the preset ID and the name of the service in the output file, or just the preset ID to also use it for the service name.
-		#[arg(short = 's', long = "service", value_parser = ServiceFromCli::from_cli, help = "PRESET_ID|id=PRESET,name=NAME")]
+		#[arg(short = 's', long = "service", value_parser = ServiceFromCli::from_cli, help = "PRESET_ID|id=PRESET,name=NAME", add = preset_candidates(PresetKind::DockerService))]
 		services: Vec<ServiceFromCli>,
 	},
 
 	/// Generates a `pre-commit` config file from a preset.
 	PreCommit {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::PreCommit))]
 		preset: String,
 
 		/// The output path of the new file [default: `.pre-commit-config.yaml`]
@@ -445,6 +774,7 @@ pub enum Commands {
 	/// Generates a `package.json` file from a preset.
 	PackageJson {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::PackageJson))]
 		preset: String,
 
 		/// The output path of the generated file [default: `package.json`]
@@ -454,6 +784,7 @@ pub enum Commands {
 	/// Generates a `.oxlintrc.json` file from a preset.
 	Oxlint {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::Oxlint))]
 		preset: String,
 
 		/// The output path of the generated file [default: `.oxlintrc.json`]
@@ -463,6 +794,7 @@ pub enum Commands {
 	/// Generates a `pnpm-workspace.yaml` file from a preset.
 	PnpmWorkspace {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::PnpmWorkspace))]
 		preset: String,
 
 		/// The output path of the generated file [default: `pnpm-workspace.yaml`]
@@ -486,3 +818,31 @@ pub enum Commands {
 		output: PathBuf,
 	},
 }
+
+impl Commands {
+	/// The id of the preset that the command generates files from, if there is one.
+	///
+	/// The templating presets are not included, since they set their own ids when they are rendered.
+	fn preset_id(&self) -> Option<&str> {
+		match self {
+			Self::Repo { preset, .. }
+			| Self::GhWorkflow { preset, .. }
+			| Self::DockerCompose { preset, .. } => preset.as_deref(),
+			Self::Gitignore { preset, .. }
+			| Self::PreCommit { preset, .. }
+			| Self::PackageJson { preset, .. }
+			| Self::Oxlint { preset, .. }
+			| Self::PnpmWorkspace { preset, .. } => Some(preset),
+			Self::Rust { command } => match command {
+				RustCommands::Crate { preset, .. } => preset.as_deref(),
+				RustCommands::Manifest { preset, .. } => Some(preset),
+			},
+			Self::Ts { command, .. } => match command {
+				TsCommands::Monorepo { root_package, .. } => root_package.as_deref(),
+				TsCommands::Package { preset, .. } => preset.as_deref(),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+impl Cli {
+	pub(super) async fn check_project(
+		dir: &Path,
+		config: Config,
+		cli_vars: IndexMap<String, Value>,
+	) -> AppResult {
+		let previous = read_generation_manifest(dir)?;
+
+		let regenerated = Self::regenerate_project(dir, &previous, config, cli_vars).await?;
+
+		let mut out_of_date: Vec<OutOfDateFile> = Vec::new();
+
+		for entry in &regenerated.files {
+			out_of_date.extend(compare_with_disk(
+				&dir.join(&entry.path),
+				&entry.path,
+				entry.content.as_deref().unwrap_or_default(),
+			)?);
+		}
+
+		for entry in &previous.files {
+			if regenerated.get_entry(&entry.path).is_none() && dir.join(&entry.path).exists() {
+				out_of_date.push(OutOfDateFile {
+					path: entry.path.clone(),
+					kind: OutOfDateKind::Extra,
+				});
+			}
+		}
+
+		report_out_of_date(out_of_date)
+	}
+}
+
+/// Compares the files generated by a command in dry-run mode with the files on disk.
+///
+/// The files managed internally by `sketch` (like the generation manifest) are not compared.
+pub(super) fn check_generated_files(dry_run: DryRun) -> AppResult {
+	let (files, _) = dry_run.into_parts();
+
+	let cwd = virtual_path(&get_cwd());
+	let internal_dir = Path::new(MANIFEST_PATH)
+		.parent()
+		.unwrap_or_else(|| Path::new(MANIFEST_PATH));
+
+	let mut out_of_date: Vec<OutOfDateFile> = Vec::new();
+
+	for (path, content) in &files {
+		if path
+			.ancestors()
+			.any(|ancestor| ancestor.ends_with(internal_dir))
+		{
+			continue;
+		}
+
+		let display_path = path.strip_prefix(&cwd).unwrap_or(path);
+
+		out_of_date.extend(compare_with_disk(path, display_path, content)?);
+	}
+
+	report_out_of_date(out_of_date)
+}
+
+fn compare_with_disk(
+	path: &Path,
+	display_path: &Path,
+	content: &[u8],
+) -> AppResult<Option<OutOfDateFile>> {
+	let kind = if !path.exists() {
+		OutOfDateKind::Missing
+	} else if read_bytes(path)? != content {
+		OutOfDateKind::Different
+	} else {
+		return Ok(None);
+	};
+
+	Ok(Some(OutOfDateFile {
+		path: display_path.to_path_buf(),
+		kind,
+	}))
+}
+
+fn report_out_of_date(out_of_date: Vec<OutOfDateFile>) -> AppResult {
+	if !out_of_date.is_empty() {
+		return Err(AppError::OutOfDateFiles(out_of_date));
+	}
+
+	emit_message("All the generated files match the presets");
+
+	Ok(())
+}
@@ -3,15 +3,37 @@
 #[macro_use]
 mod tests_macros;
 
+mod check_tests;
+mod completions_tests;
+mod config_dump_tests;
+mod config_hierarchy_tests;
+mod diagnostics_tests;
+mod diff_tests;
 mod docker_tests;
+mod dry_run_tests;
+mod env_interpolation_tests;
 mod exec_tests;
+mod generator_tests;
 mod gh_workflow_preset;
 mod gitignore_preset;
+mod import_tests;
+mod json_output_tests;
+mod manifest_tests;
+mod migration_tests;
 mod overwriting_tests;
+mod presets_inspection_tests;
+mod profiles_tests;
+mod prompts_tests;
+mod provenance_tests;
+mod remote_config_tests;
 mod rendering_tests;
 mod repo_preset_tests;
+mod rollback_tests;
 mod rust_gen_tests;
 mod ts_tests;
+mod update_tests;
+mod validation_tests;
+mod var_declarations_tests;
 mod vars_files_tests;
 
 use gh_workflow_preset::verify_generated_workflow;
@@ -0,0 +1,173 @@
+use super::*;
+
+const CONFIG: &str = indoc! {r##"
+	templates:
+	  readme: "# {{ project_name }}"
+
+	gitignore_presets:
+	  node:
+	    content:
+	      - node_modules
+
+	templating_presets:
+	  readme:
+	    templates:
+	      - template: readme
+	        output: README.md
+
+	repo_presets:
+	  app:
+	    gitignore: node
+	    with_templates:
+	      - preset_id: readme
+"##};
+
+#[test]
+fn check_command() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/check");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+
+	write_file(&config, CONFIG, true)?;
+
+	get_bin!()
+		.args([
+			"-c",
+			path_to_str!(config),
+			"--set",
+			"project_name=\"my-project\"",
+			"repo",
+			"--preset",
+			"app",
+			path_to_str!(project),
+		])
+		.assert()
+		.success();
+
+	let output = get_bin!()
+		.args(["-c", path_to_str!(config), "check", path_to_str!(project)])
+		.output()?;
+
+	assert!(output.status.success());
+	assert!(
+		String::from_utf8(output.stdout)?.contains("All the generated files match the presets")
+	);
+
+	write_file(&project.join("README.md"), "# Changed", true)?;
+	remove_file(project.join(".gitignore"))?;
+
+	let output = get_bin!()
+		.args(["-c", path_to_str!(config), "check", path_to_str!(project)])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(
+		stderr.contains("Found 2 file(s) that do not match the presets:"),
+		"{stderr}"
+	);
+	assert!(stderr.contains("`.gitignore` is missing"), "{stderr}");
+	assert!(
+		stderr.contains("`README.md` is different from the generated version"),
+		"{stderr}"
+	);
+
+	// Nothing is written
+	assert!(!project.join(".gitignore").exists());
+	assert_eq!(read_to_string(project.join("README.md"))?, "# Changed");
+
+	write_file(
+		&config,
+		&CONFIG.replace("    with_templates:\n      - preset_id: readme\n", ""),
+		true,
+	)?;
+
+	let output = get_bin!()
+		.args(["-c", path_to_str!(config), "check", path_to_str!(project)])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(
+		stderr.contains("`README.md` is no longer generated by the presets"),
+		"{stderr}"
+	);
+
+	Ok(())
+}
+
+#[test]
+fn check_flag() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/check_flag");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let pre_commit_file = out_dir.join(".pre-commit-config.yaml");
+
+	write_file(
+		&config,
+		&format!(
+			"{CONFIG}\n{}",
+			indoc! {"
+				overwrite: skip
+
+				pre_commit_presets:
+				  base:
+				    repos:
+				      - repo: https://github.com/gitleaks/gitleaks
+				        rev: v8.30.0
+				        hooks:
+				          - id: gitleaks
+			"}
+		),
+		true,
+	)?;
+
+	let pre_commit = |extra_args: &[&str]| {
+		get_bin!()
+			.args(["-c", path_to_str!(config)])
+			.args(extra_args)
+			.args(["pre-commit", "base", path_to_str!(pre_commit_file)])
+			.output()
+			.expect("Failed to run the pre-commit command")
+	};
+
+	// No generation manifest is needed
+	let output = pre_commit(&["--check"]);
+
+	assert!(!output.status.success());
+	assert!(
+		String::from_utf8(output.stderr)?
+			.contains(&format!("`{}` is missing", pre_commit_file.display()))
+	);
+	assert!(!pre_commit_file.exists());
+
+	assert!(pre_commit(&[]).status.success());
+
+	let output = pre_commit(&["--check"]);
+
+	assert!(output.status.success());
+	assert!(
+		String::from_utf8(output.stdout)?.contains("All the generated files match the presets")
+	);
+
+	// The overwrite policies do not apply, so the file is still compared
+	write_file(&pre_commit_file, "repos: []\n", true)?;
+
+	let output = pre_commit(&["--check"]);
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains(&format!(
+		"`{}` is different from the generated version",
+		pre_commit_file.display()
+	)));
+	assert_eq!(read_to_string(&pre_commit_file)?, "repos: []\n");
+
+	Ok(())
+}
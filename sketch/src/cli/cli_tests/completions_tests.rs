@@ -0,0 +1,50 @@
+use super::*;
+
+#[test]
+fn completions() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/completions");
+	reset_testing_dir(&out_dir);
+
+	// The config is detected from the cwd, like for the other commands
+	std::fs::copy(
+		examples_dir().join("presets.yaml"),
+		out_dir.join("sketch.yaml"),
+	)?;
+
+	for shell in ["bash", "zsh", "fish"] {
+		let output = get_bin!().args(["completions", shell]).output()?;
+
+		assert!(output.status.success());
+		assert!(String::from_utf8(output.stdout)?.contains("COMPLETE="));
+	}
+
+	let complete = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+		let output = get_bin!()
+			.current_dir(&out_dir)
+			.env("COMPLETE", "fish")
+			.args(["--", "sketch"])
+			.args(args)
+			.output()?;
+
+		assert!(output.status.success());
+
+		Ok(String::from_utf8(output.stdout)?
+			.lines()
+			.map(ToString::to_string)
+			.collect())
+	};
+
+	let candidates = complete(&["gitignore", ""])?;
+
+	assert!(candidates.contains(&"base".to_string()));
+	assert!(candidates.contains(&"ts".to_string()));
+
+	pretty_assert_eq!(
+		complete(&["rust", "crate", "my-crate", "--manifest", ""])?,
+		["base", "cli-custom", "cli-tools", "serde-ordered"]
+	);
+
+	pretty_assert_eq!(complete(&["gitignore", "t"])?, ["ts"]);
+
+	Ok(())
+}
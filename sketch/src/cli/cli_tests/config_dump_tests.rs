@@ -0,0 +1,52 @@
+use serde_json::json;
+
+use super::*;
+
+#[tokio::test]
+async fn config_dump() -> Result<(), Box<dyn std::error::Error>> {
+	let output_dir = PathBuf::from("tests/output/config_dump");
+
+	reset_testing_dir(&output_dir);
+
+	let config_file = examples_dir().join("presets.yaml");
+
+	let original = Config::from_file(&config_file)?;
+	let cli_vars = indexmap::indexmap! { "from_cli".to_string() => json!(true) };
+	let expected = original.to_dump(&cli_vars);
+
+	for format in ["yaml", "json", "toml"] {
+		let output = output_dir.join(format!("sketch.{format}"));
+
+		Cli::execute_with([
+			"sketch",
+			"--ignore-config",
+			"-c",
+			path_to_str!(config_file),
+			"--set",
+			"from_cli=true",
+			"config",
+			"dump",
+			path_to_str!(output),
+		])
+		.await?;
+
+		let mut dumped = Config::from_file(&output)?;
+
+		assert!(dumped.extends.is_empty());
+		assert_eq!(dumped.vars["from_cli"], json!(true));
+
+		// These are not serialized, and they depend on the file that was loaded
+		dumped
+			.config_file
+			.clone_from(&expected.config_file);
+		dumped.sources.clone_from(&expected.sources);
+
+		pretty_assert_eq!(
+			dumped,
+			expected,
+			"Dumped {format} config does not match the original"
+		);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,82 @@
+use indexmap::indexmap;
+use serde_json::json;
+
+use super::*;
+use crate::cli::config_discovery::get_hierarchical_config;
+
+#[tokio::test]
+async fn config_hierarchy() -> Result<(), Box<dyn std::error::Error>> {
+	let root = get_abs_path(Path::new("tests/config_hierarchy"))?;
+	let xdg_config = root.join("xdg/sketch.yaml");
+
+	let config = get_hierarchical_config(
+		&root.join("project/sub"),
+		Some(&root),
+		Some(xdg_config.clone()),
+	)?
+	.expect("Config not found");
+
+	pretty_assert_eq!(
+		config.vars,
+		indexmap! {
+			"from_xdg".to_string() => json!(true),
+			"layer".to_string() => json!("sub"),
+			"from_top".to_string() => json!(true),
+			"from_project".to_string() => json!(true),
+			"from_sub".to_string() => json!(true),
+		}
+	);
+
+	assert_eq!(
+		config.sources().files,
+		[
+			xdg_config.clone(),
+			root.join("sketch.yaml"),
+			root.join("project/sketch.toml"),
+			root.join("project/sub/sketch.json"),
+		]
+	);
+	assert_eq!(
+		config.config_file,
+		Some(root.join("project/sub/sketch.json"))
+	);
+
+	// Stops at the config marked as root
+	let rooted = get_hierarchical_config(
+		&root.join("rooted/sub"),
+		Some(&root),
+		Some(xdg_config.clone()),
+	)?
+	.expect("Config not found");
+
+	assert_eq!(
+		rooted.sources().files,
+		[
+			xdg_config,
+			root.join("rooted/sketch.yaml"),
+			root.join("rooted/sub/sketch.yaml"),
+		]
+	);
+	assert_eq!(rooted.vars["layer"], json!("rooted_sub"));
+
+	// Stops at the home directory
+	let from_home =
+		get_hierarchical_config(&root.join("project"), Some(&root.join("project")), None)?
+			.expect("Config not found");
+
+	assert_eq!(
+		from_home.sources().files,
+		[root.join("project/sketch.toml")]
+	);
+
+	assert!(
+		get_hierarchical_config(
+			&root.join("xdg/missing"),
+			Some(&root.join("xdg/missing")),
+			None
+		)?
+		.is_none()
+	);
+
+	Ok(())
+}
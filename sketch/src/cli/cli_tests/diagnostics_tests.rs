@@ -0,0 +1,141 @@
+use super::*;
+
+#[test]
+fn deserialization_errors_locations() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/diagnostics");
+	reset_testing_dir(&out_dir);
+
+	let yaml_config = out_dir.join("sketch.yaml");
+
+	write_file(
+		&yaml_config,
+		indoc! {"
+			# Project settings
+			templates_dir: templates
+			no_overwrite: maybe
+		"},
+		true,
+	)?;
+
+	let error = Config::from_file(&yaml_config).unwrap_err();
+
+	let AppError::DeserializationError {
+		location: Some(location),
+		..
+	} = &error
+	else {
+		panic!("Unexpected error: {error}");
+	};
+
+	assert_eq!(location.line, 3);
+	assert_eq!(location.column, 15);
+	assert_eq!(location.source_line, "no_overwrite: maybe");
+	assert_eq!(error.code(), "E0015");
+
+	let message = error.to_string();
+
+	assert!(message.contains("diagnostics/sketch.yaml:3:15\n"));
+	assert!(message.contains("3 | no_overwrite: maybe\n  |               ^"));
+
+	let toml_config = out_dir.join("sketch.toml");
+
+	write_file(
+		&toml_config,
+		indoc! {r#"
+			templates_dir = "templates"
+			no_overwrite = "maybe"
+		"#},
+		true,
+	)?;
+
+	let error = Config::from_file(&toml_config).unwrap_err();
+
+	let AppError::DeserializationError {
+		location: Some(location),
+		..
+	} = &error
+	else {
+		panic!("Unexpected error: {error}");
+	};
+
+	assert_eq!(location.line, 2);
+	assert_eq!(location.column, 16);
+
+	let json_config = out_dir.join("sketch.json");
+
+	write_file(
+		&json_config,
+		indoc! {r#"
+			{
+			  "templates_dir": "templates",
+			  "no_overwrite": "maybe"
+			}
+		"#},
+		true,
+	)?;
+
+	let error = Config::from_file(&json_config).unwrap_err();
+
+	let AppError::DeserializationError {
+		location: Some(location),
+		error: message,
+		..
+	} = &error
+	else {
+		panic!("Unexpected error: {error}");
+	};
+
+	assert_eq!(location.line, 3);
+	// The position is only shown along with the source line
+	assert!(!message.contains("at line"));
+
+	Ok(())
+}
+
+#[test]
+fn preset_suggestions() -> Result<(), Box<dyn std::error::Error>> {
+	let config = Config::from_file(examples_dir().join("presets.yaml"))?;
+
+	let error = config
+		.format_preset(PresetKind::CargoToml, "cli-tool")
+		.unwrap_err();
+
+	assert!(matches!(
+		&error,
+		AppError::PresetNotFound { suggestion: Some(suggestion), .. } if suggestion == "cli-tools"
+	));
+	assert_eq!(error.code(), "E0005");
+	assert!(
+		error
+			.to_string()
+			.ends_with("not found. Did you mean `cli-tools`?")
+	);
+
+	// Ids that are too different are not suggested
+	assert!(matches!(
+		config.format_preset(PresetKind::CargoToml, "frontend"),
+		Err(AppError::PresetNotFound {
+			suggestion: None,
+			..
+		})
+	));
+
+	let output = get_bin!()
+		.args([
+			"-c",
+			path_to_str!(examples_dir().join("presets.yaml")),
+			"show",
+			"cargo-toml",
+			"serde-order",
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(stderr.contains("[E0005]"));
+	assert!(stderr.contains("Did you mean `serde-ordered`?"));
+
+	Ok(())
+}
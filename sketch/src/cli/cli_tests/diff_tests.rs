@@ -0,0 +1,119 @@
+use std::io::Cursor;
+
+use super::*;
+
+#[test]
+fn unified_diff() -> Result<(), Box<dyn std::error::Error>> {
+	let mut output: Vec<u8> = Vec::new();
+
+	write_diff(
+		&mut output,
+		Path::new("compose.yaml"),
+		b"services:\n  db:\n    image: postgres:16\n",
+		b"services:\n  db:\n    image: postgres:17\n",
+		false,
+	)?;
+
+	assert_eq!(
+		String::from_utf8(output)?,
+		indoc! {"
+			--- a/compose.yaml
+			+++ b/compose.yaml
+			@@ -1,3 +1,3 @@
+			 services:
+			   db:
+			-    image: postgres:16
+			+    image: postgres:17
+		"}
+	);
+
+	let mut reader = Cursor::new("maybe\nyes\n".as_bytes());
+	let mut writer: Vec<u8> = Vec::new();
+
+	assert!(confirm_overwrite(
+		&mut reader,
+		&mut writer,
+		Path::new("compose.yaml")
+	)?);
+	assert!(String::from_utf8(writer)?.contains("Please answer `y` or `n`"));
+
+	// An empty answer means no
+	let mut reader = Cursor::new("\n".as_bytes());
+
+	assert!(!confirm_overwrite(
+		&mut reader,
+		&mut Vec::new(),
+		Path::new("compose.yaml")
+	)?);
+
+	Ok(())
+}
+
+#[test]
+fn diff_and_confirm_flags() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/diff");
+	reset_testing_dir(&out_dir);
+
+	let file = out_dir.join("notes.txt");
+
+	write_file(&file, "first line\nsecond line\n", true)?;
+
+	let render = |content: &str, flag: &str, input: &str| {
+		get_bin!()
+			.args([
+				"--ignore-config",
+				flag,
+				"render",
+				"--content",
+				content,
+				path_to_str!(file),
+			])
+			.write_stdin(input)
+			.output()
+			.expect("Failed to run the render command")
+	};
+
+	let output = render("first line\nchanged line\n", "--diff", "");
+
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(stdout.contains("-second line\n+changed line\n"), "{stdout}");
+	assert_eq!(read_to_string(&file)?, "first line\nchanged line\n");
+
+	// The answers cannot be read from a non-interactive input
+	let output = render("first line\nother line\n", "--confirm", "y\n");
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("because the input is not interactive"));
+	assert_eq!(read_to_string(&file)?, "first line\nchanged line\n");
+
+	// In the json output, the diff does not end up among the events
+	let output = get_bin!()
+		.args([
+			"--ignore-config",
+			"--diff",
+			"--output-format",
+			"json",
+			"render",
+			"--content",
+			"first line\nother line\n",
+			path_to_str!(file),
+		])
+		.output()?;
+
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(
+		stdout
+			.lines()
+			.all(|line| serde_json::from_str::<Value>(line).is_ok()),
+		"{stdout}"
+	);
+	assert!(String::from_utf8(output.stderr)?.contains("-changed line\n+other line\n"));
+
+	Ok(())
+}
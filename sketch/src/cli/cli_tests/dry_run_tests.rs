@@ -0,0 +1,86 @@
+use super::*;
+
+#[test]
+fn dry_run() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/dry_run");
+	reset_testing_dir(&out_dir);
+
+	let project = out_dir.join("project");
+
+	let output = get_bin!()
+		.args([
+			"-c",
+			"tests/manifest/sketch.yaml",
+			"--dry-run",
+			"repo",
+			"--preset",
+			"app",
+			path_to_str!(project),
+		])
+		.output()?;
+
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8(output.stdout)?;
+
+	for line in [
+		"Would create the directory `tests/output/dry_run/project`",
+		"Would create `tests/output/dry_run/project/.gitignore`",
+		"Would run `git init` in `tests/output/dry_run/project`",
+		"Would create `tests/output/dry_run/project/LICENSE`",
+		"Would create `tests/output/dry_run/project/README.md`",
+		"Would create `tests/output/dry_run/project/.sketch/manifest`",
+	] {
+		assert!(
+			stdout.lines().any(|l| l == line),
+			"`{line}` not found in:\n{stdout}"
+		);
+	}
+
+	assert!(!project.exists());
+
+	// Existing files are reported as overwritten
+	write_file(&out_dir.join("README.md"), "Old content", true)?;
+
+	let output = get_bin!()
+		.args([
+			"--ignore-config",
+			"--dry-run",
+			"render",
+			"--content",
+			"New content",
+			path_to_str!(out_dir.join("README.md")),
+		])
+		.output()?;
+
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(stdout.starts_with("Would overwrite `"), "{stdout}");
+	assert!(
+		stdout.ends_with("tests/output/dry_run/README.md`\n"),
+		"{stdout}"
+	);
+	assert_eq!(read_to_string(out_dir.join("README.md"))?, "Old content");
+
+	// Commands from hooks are not executed
+	let output = get_bin!()
+		.args([
+			"--ignore-config",
+			"--dry-run",
+			"exec",
+			"--cwd",
+			path_to_str!(out_dir),
+			"touch created_by_hook",
+		])
+		.output()?;
+
+	assert!(output.status.success());
+	assert!(
+		String::from_utf8(output.stdout)?.contains("Would run `sh -c 'touch created_by_hook'`")
+	);
+	assert!(!out_dir.join("created_by_hook").exists());
+
+	Ok(())
+}
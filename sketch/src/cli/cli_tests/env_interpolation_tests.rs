@@ -0,0 +1,108 @@
+use super::*;
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn env_interpolation() {
+	get_bin!()
+		.env("SKETCH_TEST_REGISTRY", "ghcr.io")
+		.env("SKETCH_TEST_TAG", "23-alpine")
+		.env("SKETCH_TEST_EMPTY", "")
+		.env_remove("SKETCH_TEST_UNSET")
+		.args([
+			"-c",
+			"tests/env_interpolation/sketch.yaml",
+			"render",
+			"--content",
+			"{{ registry }} {{ image }} {{ literal }} {{ pid }} {{ empty }}",
+		])
+		.assert()
+		.success()
+		.stdout("ghcr.io node:23-alpine ${env:SKETCH_TEST_REGISTRY} echo $$ fallback\n");
+
+	let output = get_bin!()
+		.env_remove("SKETCH_TEST_UNSET")
+		.args(["-c", "tests/env_interpolation/missing.toml", "list"])
+		.output()
+		.unwrap();
+
+	let stderr = String::from_utf8_lossy(&output.stderr);
+
+	assert!(!output.status.success());
+	assert!(
+		stderr.contains(
+			"The environment variable `SKETCH_TEST_UNSET` used in `templating_presets.my_preset.templates[0].output`"
+		),
+		"{stderr}"
+	);
+	assert!(stderr.contains("missing.toml"), "{stderr}");
+	// The error points at the expression in the original file
+	assert!(stderr.contains("missing.toml:3:11\n"), "{stderr}");
+	assert!(
+		stderr.contains("3 | output = \"${env:SKETCH_TEST_UNSET}\"\n"),
+		"{stderr}"
+	);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn env_interpolation_errors_locations() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/env_interpolation_errors");
+	reset_testing_dir(&out_dir);
+
+	let config_file = out_dir.join("sketch.yaml");
+
+	// The interpolated value is longer than the expression, which would shift the positions
+	// if they were taken from the interpolated content
+	write_file(
+		&config_file,
+		indoc! {r#"
+			templates_dir: "${env:SKETCH_TEST_DEFAULTED:-a/very/long/path/to/the/templates}"
+			vars:
+			  name: "${env:SKETCH_TEST_DEFAULTED:-a/very/long/default/name}"
+			no_overwrite: maybe
+		"#},
+		true,
+	)?;
+
+	let error = Config::from_file(&config_file).unwrap_err();
+
+	let AppError::DeserializationError {
+		location: Some(location),
+		..
+	} = &error
+	else {
+		panic!("Unexpected error: {error}");
+	};
+
+	assert_eq!(location.line, 4);
+	assert_eq!(location.column, 15);
+	assert_eq!(location.source_line, "no_overwrite: maybe");
+
+	write_file(
+		&config_file,
+		indoc! {r#"
+			vars:
+			  first: "${env:SKETCH_TEST_UNSET:-default}"
+			  escaped: "$${env:SKETCH_TEST_UNSET}"
+			  second: "prefix-${env:SKETCH_TEST_UNSET}"
+		"#},
+		true,
+	)?;
+
+	let error = Config::from_file(&config_file).unwrap_err();
+
+	let AppError::MissingEnvVar {
+		location: Some(location),
+		key_path,
+		..
+	} = &error
+	else {
+		panic!("Unexpected error: {error}");
+	};
+
+	assert_eq!(key_path, "vars.second");
+	assert_eq!(location.line, 4);
+	assert_eq!(location.column, 19);
+
+	Ok(())
+}
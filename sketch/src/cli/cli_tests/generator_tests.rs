@@ -0,0 +1,113 @@
+use super::*;
+use crate::generator::Generator;
+
+#[tokio::test]
+async fn generator() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/generator");
+	reset_testing_dir(&out_dir);
+
+	let project = out_dir.join("project");
+
+	let mut config = Config::from_file("tests/manifest/sketch.yaml")?;
+	config.no_input = false;
+
+	let generator = Generator::new(config);
+
+	// The generator never asks for the missing variables
+	assert!(generator.config().no_input);
+
+	let output = generator
+		.with_vars(IndexMap::from_iter([(
+			"description".to_string(),
+			Value::String("Generated in memory".to_string()),
+		)]))
+		.generate(
+			GenerationCommand::Repo {
+				preset: Some("app".to_string()),
+			},
+			&project,
+		)
+		.await?;
+
+	// Nothing is written to disk
+	assert!(!project.exists());
+
+	assert_eq!(output.get(".gitignore"), Some("node_modules".as_bytes()));
+	assert_eq!(
+		output.get("README.md"),
+		Some("# my-project\nGenerated in memory".as_bytes())
+	);
+	assert!(output.get("LICENSE").is_some());
+
+	// The manifest is not among the generated files
+	assert_eq!(output.files().len(), 3);
+
+	let manifest = output.manifest().expect("Manifest not found");
+
+	assert_eq!(manifest.files.len(), 3);
+
+	let commands: Vec<String> = output
+		.commands()
+		.iter()
+		.map(ToString::to_string)
+		.collect();
+
+	assert_eq!(commands, ["git init"]);
+	assert_eq!(output.commands()[0].cwd, project);
+
+	output.write()?;
+
+	assert_eq!(
+		read_to_string(project.join("README.md"))?,
+		"# my-project\nGenerated in memory"
+	);
+	assert!(!project.join(".git").exists());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn generator_skipped_files() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/generator_skipped");
+	reset_testing_dir(&out_dir);
+
+	let config_file = out_dir.join("sketch.yaml");
+
+	write_file(
+		&config_file,
+		indoc! {r##"
+			templates:
+			  readme: "# Readme"
+			  notes: "Some notes"
+
+			templating_presets:
+			  docs:
+			    overwrite: skip
+			    templates:
+			      - template: readme
+			        output: README.md
+			      - template: notes
+			        output: NOTES.md
+		"##},
+		true,
+	)?;
+
+	write_file(&out_dir.join("README.md"), "Existing readme", true)?;
+
+	let output = Generator::new(Config::from_file(&config_file)?)
+		.render_templates(
+			&out_dir,
+			vec![TemplatingPresetRef::PresetId {
+				preset_id: "docs".to_string(),
+				context: IndexMap::new(),
+			}],
+		)
+		.await?;
+
+	// The existing file is reported instead of being generated again
+	assert_eq!(output.skipped(), [output.root().join("README.md")]);
+	assert!(output.get("README.md").is_none());
+	assert_eq!(output.get("NOTES.md"), Some("Some notes".as_bytes()));
+
+	Ok(())
+}
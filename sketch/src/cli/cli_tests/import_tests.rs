@@ -0,0 +1,240 @@
+use super::*;
+
+const CONFIG: &str = indoc! {r##"
+	# Shared presets
+	typescript:
+	  package_json_presets:
+	    base:
+	      private: true # Never published
+
+	  # Added by the team
+	  ts_config_presets: {}
+
+	templates:
+	  readme: "# My project\n"
+"##};
+
+#[test]
+fn import() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/import");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+
+	write_file(&config, CONFIG, true)?;
+
+	write_file(
+		&out_dir.join("package.json"),
+		r#"{ "name": "app", "private": true, "scripts": { "build": "tsc" } }"#,
+		true,
+	)?;
+
+	write_file(
+		&out_dir.join("tsconfig.json"),
+		r#"{ "compilerOptions": { "strict": true } }"#,
+		true,
+	)?;
+
+	write_file(
+		&out_dir.join("Cargo.toml"),
+		indoc! {r#"
+			[package]
+			name = "app"
+			edition = "2024"
+
+			[dependencies]
+			serde = { version = "1", features = ["derive"] }
+		"#},
+		true,
+	)?;
+
+	for (file, id) in [
+		("package.json", "app"),
+		("tsconfig.json", "strict"),
+		("Cargo.toml", "app"),
+	] {
+		get_bin!()
+			.current_dir(&out_dir)
+			.args(["import", file, "--as", id])
+			.assert()
+			.success();
+	}
+
+	pretty_assert_eq!(
+		read_to_string(&config)?,
+		indoc! {r##"
+			# Shared presets
+			typescript:
+			  package_json_presets:
+			    base:
+			      private: true # Never published
+			    app:
+			      name: app
+			      private: true
+			      scripts:
+			        build: tsc
+
+			  # Added by the team
+			  ts_config_presets:
+			    strict:
+			      compilerOptions:
+			        strict: true
+
+			templates:
+			  readme: "# My project\n"
+			rust:
+			  manifest_presets:
+			    app:
+			      package:
+			        name: app
+			        edition: '2024'
+			      dependencies:
+			        serde:
+			          version: '1'
+			          features:
+			          - derive
+		"##}
+	);
+
+	let config = Config::from_file(&config)?;
+
+	assert!(
+		config
+			.typescript
+			.unwrap()
+			.package_json_presets
+			.contains_key("app")
+	);
+	assert!(config.rust.manifest_presets.contains_key("app"));
+
+	// The ids that already exist are rejected
+	let output = get_bin!()
+		.current_dir(&out_dir)
+		.args(["import", "package.json", "--as", "base"])
+		.output()?;
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("`base` already exists"));
+
+	let toml_config = out_dir.join("sketch.toml");
+
+	write_file(
+		&toml_config,
+		indoc! {r##"
+			# Shared presets
+			[templates]
+			readme = "# My project"
+		"##},
+		true,
+	)?;
+
+	get_bin!()
+		.current_dir(&out_dir)
+		.args([
+			"import",
+			"Cargo.toml",
+			"--as",
+			"app",
+			"--into",
+			"sketch.toml",
+		])
+		.assert()
+		.success();
+
+	pretty_assert_eq!(
+		read_to_string(&toml_config)?,
+		indoc! {r##"
+			# Shared presets
+			[templates]
+			readme = "# My project"
+
+			[rust.manifest_presets.app]
+			package = { name = "app", edition = "2024" }
+			dependencies = { serde = { version = "1", features = ["derive"] } }
+		"##}
+	);
+
+	let json_config = out_dir.join("sketch.json");
+
+	write_file(
+		&json_config,
+		indoc! {r##"
+			{
+			    "typescript": {
+			        "ts_config_presets": {}
+			    },
+			    "templates": { "readme": "# My project\n" }
+			}
+		"##},
+		true,
+	)?;
+
+	for (file, id) in [("tsconfig.json", "strict"), ("Cargo.toml", "app")] {
+		get_bin!()
+			.current_dir(&out_dir)
+			.args(["import", file, "--as", id, "--into", "sketch.json"])
+			.assert()
+			.success();
+	}
+
+	pretty_assert_eq!(
+		read_to_string(&json_config)?,
+		indoc! {r##"
+			{
+			    "typescript": {
+			        "ts_config_presets": {
+			            "strict": {
+			                "compilerOptions": {
+			                    "strict": true
+			                }
+			            }
+			        }
+			    },
+			    "templates": { "readme": "# My project\n" },
+			    "rust": {
+			        "manifest_presets": {
+			            "app": {
+			                "package": {
+			                    "name": "app",
+			                    "edition": "2024"
+			                },
+			                "dependencies": {
+			                    "serde": {
+			                        "version": "1",
+			                        "features": [
+			                            "derive"
+			                        ]
+			                    }
+			                }
+			            }
+			        }
+			    }
+			}
+		"##}
+	);
+
+	// The kind must be set when it cannot be detected from the file name
+	write_file(&out_dir.join("app.json"), r#"{ "name": "app" }"#, true)?;
+
+	get_bin!()
+		.current_dir(&out_dir)
+		.args(["import", "app.json", "--as", "other"])
+		.assert()
+		.failure();
+
+	get_bin!()
+		.current_dir(&out_dir)
+		.args([
+			"import",
+			"app.json",
+			"--as",
+			"other",
+			"--kind",
+			"package-json",
+		])
+		.args(["--into", "sketch.toml"])
+		.assert()
+		.success();
+
+	Ok(())
+}
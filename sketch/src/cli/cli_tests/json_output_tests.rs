@@ -0,0 +1,214 @@
+use super::*;
+
+const CONFIG: &str = indoc! {r##"
+	templates:
+	  readme: "# My project\n"
+
+	templating_presets:
+	  readme:
+	    templates:
+	      - template: readme
+	        output: README.md
+
+	repo_presets:
+	  app:
+	    license: MIT
+	    with_templates:
+	      - preset_id: readme
+"##};
+
+fn parse_events(stdout: Vec<u8>) -> Result<Vec<OutputEvent>, Box<dyn std::error::Error>> {
+	let stdout = String::from_utf8(stdout)?;
+
+	// Every line in the stdout must be an event
+	Ok(stdout
+		.lines()
+		.map(serde_json::from_str)
+		.collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn json_output() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/json_output");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+
+	write_file(&config, CONFIG, true)?;
+
+	let generate_repo = |extra_args: &[&str]| {
+		get_bin!()
+			.args(["--output-format", "json", "-c", path_to_str!(config)])
+			.args(extra_args)
+			.args(["repo", "--preset", "app", path_to_str!(project)])
+			.output()
+			.expect("Failed to run the repo command")
+	};
+
+	let output = generate_repo(&[]);
+
+	assert!(output.status.success());
+
+	let events = parse_events(output.stdout)?;
+
+	let project = project.canonicalize()?;
+
+	assert!(events.contains(&OutputEvent::FileCreated {
+		path: project.join("README.md"),
+		preset: Some("readme".to_string()),
+	}));
+	assert!(events.contains(&OutputEvent::FileCreated {
+		path: project.join("LICENSE"),
+		preset: Some("app".to_string()),
+	}));
+
+	let git_init = events
+		.iter()
+		.position(|event| {
+			matches!(event, OutputEvent::CommandStarted { command, cwd } if command == "git init" && cwd == &project)
+		})
+		.expect("Missing event for `git init`");
+
+	assert!(events[git_init..].iter().any(|event| {
+		matches!(
+			event,
+			OutputEvent::CommandFinished {
+				command,
+				exit_code: Some(0),
+				success: true,
+				..
+			} if command == "git init"
+		)
+	}));
+
+	let output = generate_repo(&["--overwrite", "skip"]);
+
+	assert!(output.status.success());
+
+	let events = parse_events(output.stdout)?;
+
+	assert!(events.contains(&OutputEvent::FileSkipped {
+		path: project.join("README.md"),
+		preset: Some("readme".to_string()),
+	}));
+	assert!(!events.iter().any(|event| {
+		matches!(event, OutputEvent::FileOverwritten { path, .. } if path == &project.join("README.md"))
+	}));
+
+	let output = get_bin!()
+		.args([
+			"--output-format",
+			"json",
+			"-c",
+			path_to_str!(config),
+			"repo",
+			"--preset",
+			"ap",
+			path_to_str!(project),
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let events = parse_events(output.stdout)?;
+
+	assert!(matches!(
+		events.as_slice(),
+		[OutputEvent::Error { kind, code, message }]
+			if kind == "PresetNotFound" && code == "E0005" && message.contains("Did you mean `app`?")
+	));
+
+	Ok(())
+}
+
+#[test]
+fn json_output_of_printing_commands() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/json_output_of_printing_commands");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+
+	write_file(
+		&config,
+		indoc! {r#"
+			rust:
+			  manifest_presets:
+			    base:
+			      package:
+			        edition: "2021"
+			    lib:
+			      extends_presets: [base]
+			      package:
+			        name: my-lib
+		"#},
+		true,
+	)?;
+
+	let run = |args: &[&str]| -> Result<Vec<OutputEvent>, Box<dyn std::error::Error>> {
+		let output = get_bin!()
+			.args(["--output-format", "json", "-c", path_to_str!(config)])
+			.args(args)
+			.output()?;
+
+		assert!(output.status.success(), "{args:?} failed");
+
+		parse_events(output.stdout)
+	};
+
+	let events = run(&["list", "cargo-toml"])?;
+
+	assert_eq!(
+		events,
+		[OutputEvent::PresetList {
+			kind: PresetKind::CargoToml,
+			ids: vec!["base".to_string(), "lib".to_string()],
+		}]
+	);
+
+	let events = run(&["show", "cargo-toml", "lib"])?;
+
+	assert!(
+		matches!(events.as_slice(), [OutputEvent::Output { content }] if content.contains("my-lib"))
+	);
+
+	let events = run(&["preset", "diff", "cargo-toml", "base", "lib"])?;
+
+	assert!(matches!(
+		events.as_slice(),
+		[OutputEvent::PresetDiff { changes }] if changes.len() == 1
+	));
+
+	let events = run(&["preset", "explain", "cargo-toml", "lib"])?;
+
+	assert!(matches!(
+		events.as_slice(),
+		[OutputEvent::PresetExplanation(explanation)] if explanation.extension_order == ["base", "lib"]
+	));
+
+	let events = run(&["config", "dump"])?;
+
+	assert!(matches!(events.as_slice(), [OutputEvent::Output { .. }]));
+
+	let events = run(&[
+		"--set",
+		"name=\"world\"",
+		"render",
+		"--content",
+		"Hello {{ name }}",
+	])?;
+
+	assert_eq!(
+		events,
+		[OutputEvent::Output {
+			content: "Hello world".to_string(),
+		}]
+	);
+
+	// The config is printed to stderr
+	let events = run(&["--print-config", "list", "cargo-toml"])?;
+
+	assert_eq!(events.len(), 1);
+
+	Ok(())
+}
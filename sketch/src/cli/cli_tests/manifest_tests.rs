@@ -0,0 +1,122 @@
+use serde_json::json;
+
+use super::*;
+
+#[tokio::test]
+async fn generation_manifest() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/manifest");
+
+	if out_dir.exists() {
+		remove_dir_all(&out_dir)?;
+	}
+
+	get_bin!()
+		.args([
+			"-c",
+			"tests/manifest/sketch.yaml",
+			"repo",
+			"--preset",
+			"app",
+			out_dir.to_str().unwrap(),
+		])
+		.assert()
+		.success();
+
+	let manifest = GenerationManifest::from_dir(&out_dir)?.expect("Manifest not found");
+
+	assert_eq!(
+		manifest.command,
+		GenerationCommand::Repo {
+			preset: Some("app".to_string())
+		}
+	);
+
+	let paths: Vec<&Path> = manifest
+		.files
+		.iter()
+		.map(|entry| entry.path.as_path())
+		.collect();
+
+	assert_eq!(
+		paths,
+		[
+			Path::new(".gitignore"),
+			Path::new("LICENSE"),
+			Path::new("README.md")
+		]
+	);
+
+	for entry in &manifest.files {
+		let content = std::fs::read(out_dir.join(&entry.path))?;
+
+		assert_eq!(entry.hash, hash_content(&content));
+
+		// A copy of each file is stored next to the manifest
+		assert_eq!(
+			std::fs::read(
+				out_dir
+					.join(GENERATED_FILES_DIR)
+					.join(&entry.path)
+			)?,
+			content
+		);
+	}
+
+	let gitignore = &manifest.files[0];
+
+	assert_eq!(gitignore.preset_kind, PresetKind::Gitignore);
+	assert_eq!(gitignore.preset_id.as_deref(), Some("node"));
+
+	let license = &manifest.files[1];
+
+	assert_eq!(license.preset_kind, PresetKind::Repo);
+	assert_eq!(license.preset_id.as_deref(), Some("app"));
+	assert_eq!(license.template, None);
+
+	let readme = &manifest.files[2];
+
+	assert_eq!(readme.preset_kind, PresetKind::Templates);
+	assert_eq!(readme.preset_id.as_deref(), Some("readme"));
+	assert_eq!(readme.template.as_deref(), Some("readme"));
+	assert_eq!(readme.vars["project_name"], json!("my-project"));
+	assert_eq!(readme.vars["description"], json!("A new project"));
+
+	Ok(())
+}
+
+#[test]
+fn manifest_from_library() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/manifest_from_library");
+	reset_testing_dir(&out_dir);
+
+	let config = Config::from_file(Path::new("tests/manifest/sketch.yaml"))?;
+	let preset = config.get_repo_preset("app")?;
+
+	let mut manifest = GenerationManifest::new(
+		&out_dir,
+		GenerationCommand::Repo {
+			preset: Some("app".to_string()),
+		},
+		&IndexMap::new(),
+	);
+
+	// The written files are tracked even outside of the cli
+	config.init_repo(preset, None, &out_dir, &IndexMap::new(), &mut manifest)?;
+
+	let paths: Vec<&Path> = manifest
+		.files
+		.iter()
+		.map(|entry| entry.path.as_path())
+		.collect();
+
+	assert_eq!(
+		paths,
+		[
+			Path::new(".gitignore"),
+			Path::new("LICENSE"),
+			Path::new("README.md")
+		]
+	);
+
+	Ok(())
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+#[tokio::test]
+async fn config_migration() -> Result<(), Box<dyn std::error::Error>> {
+	let output_dir = PathBuf::from("tests/output/migration");
+
+	reset_testing_dir(&output_dir);
+
+	let old_config = output_dir.join("sketch.yaml");
+
+	std::fs::copy("tests/migration/v0.5.yaml", &old_config)?;
+
+	let error = Config::from_file(&old_config).unwrap_err();
+
+	assert!(
+		error
+			.to_string()
+			.contains("This config file seems to use an older format")
+	);
+
+	let report = migrate_config_file(&old_config)?;
+
+	pretty_assert_eq!(
+		report,
+		MigrationReport {
+			from_version: None,
+			changes: vec![
+				"Moved `git_presets` to `repo_presets`".to_string(),
+				"Moved `cargo_toml_presets` to `rust.manifest_presets`".to_string(),
+				"Changed the level of `rust.manifest_presets.my_manifest.lints.clippy.pedantic` from `force-warn` to `warn`".to_string(),
+				"Moved `typescript.oxlint_presets.my_oxlint.extras` to `typescript.oxlint_presets.my_oxlint`".to_string(),
+				"Renamed `id` to `preset_id` in 1 templating preset reference(s)".to_string(),
+			],
+			removed: vec![
+				"repo_presets.my_repo.hooks_pre".to_string(),
+				"rust.manifest_presets.my_manifest.lints.clippy.pedantic.config".to_string(),
+				"rust.manifest_presets.my_manifest.bin[0].edition".to_string(),
+				"templating_presets.readme.templates[0].context".to_string(),
+			],
+		}
+	);
+
+	let config = Config::from_file(&old_config)?;
+
+	assert_eq!(config.version.as_deref(), Some(CONFIG_VERSION));
+	// The existing presets are kept along with the migrated ones
+	assert!(config.repo_presets.contains_key("my_repo"));
+	assert!(config.repo_presets.contains_key("other_repo"));
+	assert!(
+		config
+			.rust
+			.manifest_presets
+			.contains_key("my_manifest")
+	);
+	assert!(
+		config
+			.rust
+			.manifest_presets
+			.contains_key("other_manifest")
+	);
+	assert!(
+		config.typescript.unwrap().oxlint_presets["my_oxlint"]
+			.config
+			.extras
+			.contains_key("jsPlugins")
+	);
+	assert!(read_to_string(&old_config)?.starts_with("version: '0.6'\n"));
+
+	// Migrating an up to date config does nothing
+	let report = migrate_config_file(&old_config)?;
+
+	assert!(report.changes.is_empty() && report.removed.is_empty());
+
+	// Presets with the same id in the old and the new location are not overwritten
+	let conflicting_config = output_dir.join("conflict.yaml");
+
+	std::fs::copy("tests/migration/conflict.yaml", &conflicting_config)?;
+
+	let error = migrate_config_file(&conflicting_config).unwrap_err();
+
+	assert!(
+		error
+			.to_string()
+			.contains("the key `my_repo` is present in both")
+	);
+	assert_eq!(
+		read_to_string(&conflicting_config)?,
+		read_to_string("tests/migration/conflict.yaml")?
+	);
+
+	let newer_config = Config::from_file("tests/migration/newer.toml");
+
+	assert!(matches!(
+		newer_config,
+		Err(AppError::NewerConfigVersion { version, .. }) if version == "99.0"
+	));
+
+	Ok(())
+}
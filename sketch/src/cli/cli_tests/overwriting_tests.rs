@@ -34,3 +34,170 @@ async fn overwrite_test() -> Result<(), Box<dyn std::error::Error>> {
 
 	Ok(())
 }
+
+const POLICIES_CONFIG: &str = indoc! {r##"
+	overwrite_rules:
+	  LICENSE: skip
+	  .gitignore: skip
+	  README.md: backup
+
+	templates:
+	  readme: "# {{ project_name }}\n"
+	  notes: "Notes for {{ project_name }}\n"
+
+	gitignore_presets:
+	  node:
+	    content:
+	      - node_modules
+
+	templating_presets:
+	  readme:
+	    templates:
+	      - template: readme
+	        output: README.md
+	  notes:
+	    overwrite: error
+	    templates:
+	      - template: notes
+	        output: NOTES.md
+
+	repo_presets:
+	  app:
+	    gitignore: node
+	    license: MIT
+	    with_templates:
+	      - preset_id: readme
+"##};
+
+#[test]
+fn overwrite_policies() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/overwrite_policies");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+
+	write_file(&config, POLICIES_CONFIG, true)?;
+
+	let generate_repo = |project_name: &str| {
+		get_bin!()
+			.args([
+				"-c",
+				path_to_str!(config),
+				"--set",
+				&format!("project_name=\"{project_name}\""),
+				"repo",
+				"--preset",
+				"app",
+				path_to_str!(project),
+			])
+			.output()
+			.expect("Failed to run the repo command")
+	};
+
+	assert!(generate_repo("first").status.success());
+
+	write_file(&project.join("LICENSE"), "My license\n", true)?;
+	write_file(&project.join(".gitignore"), "target\n", true)?;
+	write_file(&project.join("README.md"), "# Local readme\n", true)?;
+
+	let output = generate_repo("second");
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(stdout.contains(
+		"Skipped `tests/output/overwrite_policies/project/LICENSE`, since it already exists"
+	));
+
+	assert_eq!(read_to_string(project.join("LICENSE"))?, "My license\n");
+	assert_eq!(read_to_string(project.join(".gitignore"))?, "target\n");
+	assert_eq!(read_to_string(project.join("README.md"))?, "# second\n");
+	assert_eq!(
+		read_to_string(project.join("README.md.orig"))?,
+		"# Local readme\n"
+	);
+
+	// Policy of a templating preset
+	let render_notes = |extra_args: &[&str]| {
+		get_bin!()
+			.args(["-c", path_to_str!(config)])
+			.args(extra_args)
+			.args([
+				"--set",
+				"project_name=\"notes\"",
+				"render",
+				"--preset",
+				"notes",
+				path_to_str!(project),
+			])
+			.output()
+			.expect("Failed to run the render command")
+	};
+
+	assert!(render_notes(&[]).status.success());
+
+	let output = render_notes(&[]);
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("already exists"));
+
+	// The rules take precedence over the policy of the preset
+	write_file(
+		&config,
+		&POLICIES_CONFIG.replace("README.md: backup", "NOTES.md: skip"),
+		true,
+	)?;
+
+	let output = render_notes(&["--dry-run"]);
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(stdout.contains(
+		"Would skip `tests/output/overwrite_policies/project/NOTES.md`, since it already exists"
+	));
+
+	Ok(())
+}
+
+#[test]
+fn ask_overwrite_policy() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/ask_overwrite_policy");
+	reset_testing_dir(&out_dir);
+
+	let output_file = out_dir.join("file.txt");
+
+	write_file(&output_file, "old\n", true)?;
+
+	let render = |answer: &str| {
+		get_bin!()
+			.args([
+				"--ignore-config",
+				"--overwrite",
+				"ask",
+				"render",
+				"--content",
+				"new\n",
+				path_to_str!(output_file),
+			])
+			.write_stdin(answer)
+			.output()
+			.expect("Failed to run the render command")
+	};
+
+	// The answers cannot be read from a non-interactive input
+	let output = render("y\n");
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(!output.status.success());
+	// Rendered templates use absolute paths
+	assert!(
+		stderr.contains(
+			"ask_overwrite_policy/file.txt` should be applied, because the input is not interactive"
+		),
+		"{stderr}"
+	);
+	assert_eq!(read_to_string(&output_file)?, "old\n");
+
+	Ok(())
+}
@@ -0,0 +1,281 @@
+use super::*;
+
+use crate::docker::ComposeFile;
+
+#[tokio::test]
+async fn list_and_show_presets() -> Result<(), Box<dyn std::error::Error>> {
+	let config = Config::from_file(examples_dir().join("presets.yaml"))?;
+
+	pretty_assert_eq!(
+		config.preset_ids(PresetKind::CargoToml),
+		["base", "cli-custom", "cli-tools", "serde-ordered"]
+	);
+	assert!(
+		config
+			.preset_ids(PresetKind::RustCrate)
+			.is_empty()
+	);
+
+	let manifest = config.format_preset(PresetKind::CargoToml, "cli-custom")?;
+
+	assert!(manifest.contains("[dependencies]"));
+	assert!(manifest.contains(r#"serde = { version = "1", features = ["preserve_order"] }"#));
+
+	let compose_file = config.format_preset(PresetKind::ComposeFile, "extended")?;
+	let compose_file: ComposeFile = serde_yaml_ng::from_str(&compose_file)?;
+
+	assert!(
+		compose_file
+			.services
+			.get("db")
+			.unwrap()
+			.clone()
+			.as_config()
+			.is_some_and(|db| db.image.as_deref() == Some("postgres"))
+	);
+
+	let gitignore = config.format_preset(PresetKind::Gitignore, "ts")?;
+
+	pretty_assert_eq!(gitignore, "*.env\ndist\n*.tsBuildInfo\nnode_modules");
+
+	assert!(matches!(
+		config.format_preset(PresetKind::GithubWorkflowStep, "missing"),
+		Err(AppError::PresetNotFound { .. })
+	));
+
+	Ok(())
+}
+
+#[test]
+fn diff_presets() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/presets_diff");
+	reset_testing_dir(&out_dir);
+
+	let config_file = out_dir.join("sketch.yaml");
+
+	write_file(
+		&config_file,
+		indoc! {r#"
+			rust:
+			  manifest_presets:
+			    base:
+			      package:
+			        edition: "2021"
+			        license: MIT
+			    rust-lib:
+			      extends_presets: [base]
+			      package:
+			        version: 0.1.0
+			      dependencies:
+			        serde: "1"
+			    rust-lib-strict:
+			      extends_presets: [rust-lib]
+			      package:
+			        edition: "2024"
+			        license: null
+			      lints:
+			        clippy:
+			          unwrap_used: deny
+		"#},
+		true,
+	)?;
+
+	let config = Config::from_file(&config_file)?;
+
+	let changes = config.diff_presets(PresetKind::CargoToml, "rust-lib", "rust-lib-strict")?;
+
+	pretty_assert_eq!(
+		changes,
+		[
+			ValueChange::Changed {
+				path: "package.edition".to_string(),
+				old: "2021".into(),
+				new: "2024".into(),
+			},
+			ValueChange::Added {
+				path: "lints".to_string(),
+				value: serde_json::json!({ "clippy": { "unwrap_used": "deny" } }),
+			},
+		]
+	);
+
+	pretty_assert_eq!(
+		diff_values(
+			&serde_json::json!({ "exports": { "./index": "a" }, "files": ["a", "b"] }),
+			&serde_json::json!({ "exports": {}, "files": ["a"] }),
+		),
+		[
+			ValueChange::Removed {
+				path: r#"exports."./index""#.to_string(),
+				value: "a".into(),
+			},
+			ValueChange::Removed {
+				path: "files[1]".to_string(),
+				value: "b".into(),
+			},
+		]
+	);
+
+	let output = get_bin!()
+		.args(["-c", path_to_str!(config_file)])
+		.args(["preset", "diff", "cargo-toml", "base", "rust-lib"])
+		.output()?;
+
+	assert!(output.status.success());
+
+	pretty_assert_eq!(
+		String::from_utf8(output.stdout)?,
+		indoc! {r#"
+			+ package.version: "0.1.0"
+			+ dependencies: {"serde":"1"}
+		"#}
+	);
+
+	// Presets that cannot extend other presets are not supported
+	assert!(
+		config
+			.diff_presets(PresetKind::Repo, "a", "b")
+			.is_err()
+	);
+
+	Ok(())
+}
+
+#[test]
+fn explain_presets() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/presets_explain");
+	reset_testing_dir(&out_dir);
+
+	let config_file = out_dir.join("sketch.yaml");
+
+	write_file(
+		&config_file,
+		indoc! {r#"
+			rust:
+			  manifest_presets:
+			    base:
+			      package:
+			        edition: "2021"
+			        license: MIT
+			    strict-lints:
+			      package:
+			        edition: "2021"
+			      lints:
+			        clippy:
+			          unwrap_used: deny
+			    rust-lib:
+			      extends_presets: [base]
+			      package:
+			        version: 0.1.0
+			    rust-lib-strict:
+			      extends_presets: [rust-lib, strict-lints]
+			      package:
+			        edition: "2024"
+
+			typescript:
+			  package_json_presets:
+			    base:
+			      license: MIT
+			    app:
+			      extends_presets: [base]
+			      name: app
+			    explicit-app:
+			      extends_presets: [app]
+			      private: true
+			      type: module
+		"#},
+		true,
+	)?;
+
+	let config = Config::from_file(&config_file)?;
+
+	let explanation = config.explain_preset(PresetKind::CargoToml, "rust-lib-strict")?;
+
+	// The first extended preset is merged last, so it has a higher priority than the following ones
+	pretty_assert_eq!(
+		explanation.extension_order,
+		["strict-lints", "base", "rust-lib", "rust-lib-strict"]
+	);
+
+	let origins: Vec<(&str, &str)> = explanation
+		.fields
+		.iter()
+		.map(|field| (field.path.as_str(), field.preset_id.as_str()))
+		.collect();
+
+	pretty_assert_eq!(
+		origins,
+		[
+			("package.version", "rust-lib"),
+			("package.edition", "rust-lib-strict"),
+			("package.license", "base"),
+			("lints.clippy.unwrap_used", "strict-lints"),
+		]
+	);
+
+	// The values that are not set by any preset come from the defaults
+	let explanation = config.explain_preset(PresetKind::PackageJson, "app")?;
+
+	let origins: Vec<(&str, &str)> = explanation
+		.fields
+		.iter()
+		.map(|field| (field.path.as_str(), field.preset_id.as_str()))
+		.collect();
+
+	pretty_assert_eq!(
+		origins,
+		[
+			("name", "app"),
+			("private", "default"),
+			("version", "default"),
+			("type", "default"),
+			("scripts", "default"),
+			("license", "base"),
+			("dependencies", "default"),
+			("devDependencies", "default"),
+		]
+	);
+
+	// The values that are set explicitly are not labelled as defaults, even if they are equal to them
+	let explanation = config.explain_preset(PresetKind::PackageJson, "explicit-app")?;
+
+	let origins: Vec<(&str, &str)> = explanation
+		.fields
+		.iter()
+		.map(|field| (field.path.as_str(), field.preset_id.as_str()))
+		.collect();
+
+	pretty_assert_eq!(
+		origins,
+		[
+			("name", "app"),
+			("private", "explicit-app"),
+			("version", "default"),
+			("type", "explicit-app"),
+			("scripts", "default"),
+			("license", "base"),
+			("dependencies", "default"),
+			("devDependencies", "default"),
+		]
+	);
+
+	let output = get_bin!()
+		.args(["-c", path_to_str!(config_file)])
+		.args(["preset", "explain", "cargo-toml", "rust-lib"])
+		.output()?;
+
+	assert!(output.status.success());
+
+	pretty_assert_eq!(
+		String::from_utf8(output.stdout)?,
+		indoc! {r#"
+			Extension order: base -> rust-lib
+
+			package.version: "0.1.0"  # rust-lib
+			package.edition: "2021"   # base
+			package.license: "MIT"    # base
+		"#}
+	);
+
+	Ok(())
+}
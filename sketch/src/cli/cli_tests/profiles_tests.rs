@@ -0,0 +1,81 @@
+use serde_json::json;
+
+use super::*;
+
+#[tokio::test]
+async fn config_profiles() -> Result<(), Box<dyn std::error::Error>> {
+	let base_file = get_abs_path(Path::new("tests/profiles/base.yaml"))?;
+	let root_file = get_abs_path(Path::new("tests/profiles/sketch.yaml"))?;
+
+	let mut config = Config::from_file(&root_file)?;
+
+	assert_eq!(config.active_profile(), None);
+	assert_eq!(config.vars["visibility"], json!("private"));
+
+	config.apply_profile("oss")?;
+
+	assert_eq!(config.active_profile(), Some("oss"));
+	assert_eq!(config.vars["org"], json!("my-org"));
+	// Profiles with the same name from different files are merged together
+	assert_eq!(config.vars["visibility"], json!("public"));
+	assert_eq!(config.vars["license"], json!("Apache-2.0"));
+	assert_eq!(
+		config
+			.get_templating_preset("readme")?
+			.templates
+			.len(),
+		1
+	);
+	assert_eq!(
+		config.sources().var_source("visibility"),
+		Some(base_file.as_path())
+	);
+	assert_eq!(
+		config.sources().presets[&(PresetKind::Templates, "readme".to_string())],
+		[root_file.clone(), root_file]
+	);
+
+	let error = Config::from_file("tests/profiles/sketch.yaml")?
+		.apply_profile("missing")
+		.unwrap_err();
+
+	assert!(
+		error
+			.to_string()
+			.contains("Profile `missing` not found. The available profiles are: [ oss, internal ]")
+	);
+
+	let output = get_bin!()
+		.env("SKETCH_PROFILE", "internal")
+		.args([
+			"-c",
+			"tests/profiles/sketch.yaml",
+			"--print-config",
+			"render",
+			"--content",
+			"{{ license }}",
+		])
+		.output()?;
+
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(stdout.starts_with("Active profile: internal\n"), "{stdout}");
+	assert!(stdout.ends_with("Proprietary\n"), "{stdout}");
+
+	get_bin!()
+		.env("SKETCH_PROFILE", "internal")
+		.args([
+			"-c",
+			"tests/profiles/sketch.yaml",
+			"--profile",
+			"oss",
+			"render",
+			"--content",
+			"{{ license }}",
+		])
+		.assert()
+		.success()
+		.stdout("Apache-2.0\n");
+
+	Ok(())
+}
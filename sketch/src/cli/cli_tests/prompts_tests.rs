@@ -0,0 +1,152 @@
+use std::io::Cursor;
+
+use serde_json::json;
+
+use super::*;
+
+fn prompt_with_input(
+	input: &str,
+	name: &str,
+	declaration: Option<&VarDeclaration>,
+) -> (AppResult<Option<Value>>, String) {
+	let mut reader = Cursor::new(input.as_bytes());
+	let mut writer: Vec<u8> = Vec::new();
+
+	let result = prompt_var(&mut reader, &mut writer, name, declaration);
+
+	(result, String::from_utf8(writer).unwrap())
+}
+
+#[test]
+fn var_prompts() {
+	let license = VarDeclaration {
+		kind: Some(VarType::String),
+		description: Some("The license of the project".to_string()),
+		default: Some(json!("MIT")),
+		allowed_values: vec![json!("MIT"), json!("Apache-2.0")],
+		..Default::default()
+	};
+
+	let (result, output) = prompt_with_input("\n", "license", Some(&license));
+
+	assert_eq!(result.unwrap(), Some(json!("MIT")));
+	assert_eq!(
+		output,
+		"license (The license of the project) [default: MIT] [choices: MIT, Apache-2.0]: "
+	);
+
+	let (result, output) = prompt_with_input("GPL-3.0\nApache-2.0\n", "license", Some(&license));
+
+	assert_eq!(result.unwrap(), Some(json!("Apache-2.0")));
+	assert!(output.contains("`GPL-3.0` is not one of the allowed choices"));
+
+	let year = VarDeclaration {
+		kind: Some(VarType::Integer),
+		..Default::default()
+	};
+
+	let (result, output) = prompt_with_input("next year\n2030\n", "year", Some(&year));
+
+	assert_eq!(result.unwrap(), Some(json!(2030)));
+	assert!(output.contains("`next year` is not a valid integer"));
+
+	// Optional vars can be skipped
+	let (result, _) = prompt_with_input("\n", "year", Some(&year));
+
+	assert_eq!(result.unwrap(), None);
+
+	// Undeclared vars are always required
+	let (result, output) = prompt_with_input("\nmy-project\n", "project_name", None);
+
+	assert_eq!(result.unwrap(), Some(json!("my-project")));
+	assert!(output.contains("A value is required"));
+
+	let (result, _) = prompt_with_input("", "project_name", None);
+
+	assert!(
+		result
+			.unwrap_err()
+			.to_string()
+			.contains("The input was closed before a value for `project_name` was provided")
+	);
+}
+
+#[test]
+fn missing_vars_without_input() -> Result<(), Box<dyn std::error::Error>> {
+	// The variables that can be left undefined do not cause errors
+	get_bin!()
+		.args([
+			"--ignore-config",
+			"--no-input",
+			"render",
+			"--content",
+			"{{ x | default(value=1) }}{% if flag %}{{ flag }}{% endif %}{% if name is undefined %}none{% endif %}{% set local = 1 %}{{ local }}",
+		])
+		.assert()
+		.success()
+		.stdout("1none1\n");
+
+	// The other missing variables are reported by the template engine
+	let output = get_bin!()
+		.args([
+			"--ignore-config",
+			"--no-input",
+			"render",
+			"--content",
+			"{{ name }}",
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(
+		stderr.contains("Variable `name` not found in context"),
+		"{stderr}"
+	);
+
+	// Non-interactive input behaves like `--no-input`
+	let output = get_bin!()
+		.args(["--ignore-config", "render", "--content", "{{ name }}"])
+		.write_stdin("my-project\n")
+		.output()?;
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("Variable `name` not found in context"));
+
+	// Required variables are reported before rendering anything
+	let output = get_bin!()
+		.args([
+			"-c",
+			"tests/var_declarations/sketch.yaml",
+			"--set",
+			"year=2030",
+			"render",
+			"--preset",
+			"invalid",
+			"tests/output/missing_vars_without_input",
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+	assert!(
+		String::from_utf8(output.stderr)?
+			.contains("`owner` (templating preset `invalid`): is required but was not set")
+	);
+
+	get_bin!()
+		.args([
+			"--ignore-config",
+			"--set",
+			"name=\"my-project\"",
+			"render",
+			"--content",
+			"{{ name }}",
+		])
+		.assert()
+		.success()
+		.stdout("my-project\n");
+
+	Ok(())
+}
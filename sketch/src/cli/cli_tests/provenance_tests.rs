@@ -0,0 +1,67 @@
+use super::*;
+
+#[tokio::test]
+async fn config_provenance() -> Result<(), Box<dyn std::error::Error>> {
+	let base_file = get_abs_path(Path::new("tests/provenance/base.yaml"))?;
+	let root_file = get_abs_path(Path::new("tests/provenance/sketch.yaml"))?;
+
+	let config = Config::from_file(&root_file)?;
+	let sources = config.sources();
+
+	assert_eq!(sources.files, [base_file.clone(), root_file.clone()]);
+
+	assert_eq!(
+		sources.preset_source(PresetKind::Templates, "base_preset"),
+		Some(root_file.as_path())
+	);
+	assert_eq!(
+		sources.presets[&(PresetKind::Templates, "base_preset".to_string())],
+		[base_file.clone(), root_file.clone()]
+	);
+	assert_eq!(
+		sources.preset_source(PresetKind::Templates, "loop_a"),
+		Some(base_file.as_path())
+	);
+	assert_eq!(
+		sources.template_source("greeting"),
+		Some(base_file.as_path())
+	);
+	assert_eq!(sources.var_source("name"), Some(root_file.as_path()));
+
+	let not_found = Cli::execute_with([
+		"sketch",
+		"-c",
+		"tests/provenance/sketch.yaml",
+		"render",
+		"--preset",
+		"missing",
+		"tests/output/provenance",
+	])
+	.await
+	.unwrap_err()
+	.to_string();
+
+	assert!(not_found.starts_with("Templates preset `missing` not found"));
+	assert!(not_found.contains(&format!("  - `{}`", base_file.display())));
+	assert!(not_found.contains(&format!("  - `{}`", root_file.display())));
+
+	let circular = Cli::execute_with([
+		"sketch",
+		"-c",
+		"tests/provenance/sketch.yaml",
+		"render",
+		"--preset",
+		"loop_a",
+		"tests/output/provenance",
+	])
+	.await
+	.unwrap_err();
+
+	assert!(matches!(circular, AppError::CircularDependency(_)));
+	assert!(circular.to_string().contains(&format!(
+		"loop_a (`{0}`) -> loop_b (`{0}`) -> loop_a (`{0}`)",
+		base_file.display()
+	)));
+
+	Ok(())
+}
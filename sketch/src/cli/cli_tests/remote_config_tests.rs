@@ -0,0 +1,171 @@
+use std::{
+	process::Command,
+	time::{Duration, SystemTime},
+};
+
+use super::*;
+
+fn git(dir: &Path, args: &[&str]) {
+	let output = Command::new("git")
+		.args([
+			"-c",
+			"user.name=sketch",
+			"-c",
+			"user.email=sketch@example.com",
+		])
+		.args(args)
+		.current_dir(dir)
+		.output()
+		.expect("Failed to run git");
+
+	assert!(
+		output.status.success(),
+		"git {} failed: {}",
+		args.join(" "),
+		String::from_utf8_lossy(&output.stderr)
+	);
+}
+
+#[tokio::test]
+async fn remote_config() -> Result<(), Box<dyn std::error::Error>> {
+	let output_dir = get_abs_path(Path::new("tests/output"))?.join("remote_config");
+	let work_dir = output_dir.join("work");
+	let bare_repo = output_dir.join("repo.git");
+	let cache_dir = output_dir.join("cache");
+
+	reset_testing_dir(&output_dir);
+	create_all_dirs(&work_dir.join("configs"))?;
+
+	let local_config = output_dir.join("sketch.yaml");
+
+	git(&work_dir, &["init", "--quiet"]);
+
+	write_file(
+		&work_dir.join("configs/base.yaml"),
+		"templating_presets:\n  first_version: {}\n",
+		true,
+	)?;
+	git(&work_dir, &["add", "-A"]);
+	git(&work_dir, &["commit", "--quiet", "-m", "first"]);
+	git(&work_dir, &["tag", "v1"]);
+
+	// Extends the local config, which in turn extends this one
+	write_file(
+		&work_dir.join("configs/base.yaml"),
+		"extends: [\"other.yaml\"]\ntemplating_presets:\n  latest_version: {}\n",
+		true,
+	)?;
+	write_file(
+		&work_dir.join("configs/other.yaml"),
+		&format!("extends: [\"{}\"]\n", local_config.display()),
+		true,
+	)?;
+	git(&work_dir, &["add", "-A"]);
+	git(&work_dir, &["commit", "--quiet", "-m", "second"]);
+
+	git(
+		&output_dir,
+		&["clone", "--quiet", "--bare", "work", "repo.git"],
+	);
+
+	let repo_url = format!("file://{}", bare_repo.display());
+
+	write_file(
+		&local_config,
+		&format!("extends:\n  - repo: {repo_url}\n    rev: v1\n    path: configs/base.yaml\n"),
+		true,
+	)?;
+
+	let output = get_bin!()
+		.env("XDG_CACHE_HOME", &cache_dir)
+		.args(["-c", path_to_str!(local_config), "list", "templates"])
+		.output()?;
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	assert_eq!(std::str::from_utf8(&output.stdout)?, "first_version\n");
+
+	let cached_repos: Vec<_> = std::fs::read_dir(cache_dir.join("sketch/repos"))?
+		.map(|entry| entry.map(|e| e.file_name()))
+		.collect::<Result<_, _>>()?;
+
+	assert_eq!(cached_repos.len(), 1);
+
+	// The cached clone is reused without being fetched again, even if the remote is gone
+	remove_dir_all(&bare_repo)?;
+
+	get_bin!()
+		.env("XDG_CACHE_HOME", &cache_dir)
+		.args(["-c", path_to_str!(local_config), "list", "templates"])
+		.assert()
+		.success()
+		.stdout("first_version\n")
+		.stderr("");
+
+	// Circular chain across local and remote files
+	git(
+		&output_dir,
+		&["clone", "--quiet", "--bare", "work", "repo.git"],
+	);
+
+	write_file(
+		&local_config,
+		&format!("extends:\n  - repo: {repo_url}\n    path: configs/base.yaml\n"),
+		true,
+	)?;
+
+	let output = get_bin!()
+		.env("XDG_CACHE_HOME", &cache_dir)
+		.args(["-c", path_to_str!(local_config), "list", "templates"])
+		.output()?;
+
+	let stderr = String::from_utf8_lossy(&output.stderr);
+
+	assert!(!output.status.success());
+	assert!(
+		stderr.contains("Found circular dependency to the config file"),
+		"{stderr}"
+	);
+
+	// The clones of the default branch are updated with the new commits
+	write_file(
+		&work_dir.join("configs/base.yaml"),
+		"templating_presets:\n  refreshed_version: {}\n",
+		true,
+	)?;
+	git(&work_dir, &["commit", "--quiet", "-am", "third"]);
+
+	remove_dir_all(&bare_repo)?;
+	git(
+		&output_dir,
+		&["clone", "--quiet", "--bare", "work", "repo.git"],
+	);
+
+	// The clones that were fetched recently are not updated
+	get_bin!()
+		.env("XDG_CACHE_HOME", &cache_dir)
+		.args(["-c", path_to_str!(local_config), "list", "templates"])
+		.assert()
+		.failure();
+
+	for entry in std::fs::read_dir(cache_dir.join("sketch/repos"))? {
+		let marker = entry?.path().join(".git/sketch-fetched");
+
+		File::options()
+			.write(true)
+			.open(marker)?
+			.set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))?;
+	}
+
+	get_bin!()
+		.env("XDG_CACHE_HOME", &cache_dir)
+		.args(["-c", path_to_str!(local_config), "list", "templates"])
+		.assert()
+		.success()
+		.stdout("refreshed_version\n");
+
+	Ok(())
+}
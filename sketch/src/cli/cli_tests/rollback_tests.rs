@@ -0,0 +1,95 @@
+use super::*;
+
+const CONFIG: &str = indoc! {r##"
+	templates:
+	  readme: "# My project\n"
+	  broken: "{{ throw(message='Broken template') }}"
+
+	templating_presets:
+	  readme:
+	    templates:
+	      - template: readme
+	        output: README.md
+	  broken:
+	    templates:
+	      - template: broken
+	        output: BROKEN.md
+
+	repo_presets:
+	  app:
+	    license: MIT
+	    with_templates:
+	      - preset_id: readme
+	      - preset_id: broken
+"##};
+
+#[test]
+fn rollback_on_failure() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/rollback");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+
+	write_file(&config, CONFIG, true)?;
+
+	let generate_repo = || {
+		get_bin!()
+			.args([
+				"-c",
+				path_to_str!(config),
+				"repo",
+				"--preset",
+				"app",
+				path_to_str!(project),
+			])
+			.output()
+			.expect("Failed to run the repo command")
+	};
+
+	let output = generate_repo();
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("Failed to render the template `broken`"));
+
+	// The new directory is removed entirely
+	assert!(!project.exists());
+
+	create_dir_all(&project)?;
+	write_file(&project.join("notes.txt"), "My notes\n", true)?;
+	write_file(&project.join("LICENSE"), "My license\n", true)?;
+
+	assert!(!generate_repo().status.success());
+
+	// The pre-existing files are left untouched, and everything else is removed
+	assert_eq!(read_to_string(project.join("notes.txt"))?, "My notes\n");
+	assert_eq!(read_to_string(project.join("LICENSE"))?, "My license\n");
+
+	for path in [".gitignore", ".git", "README.md", ".sketch"] {
+		assert!(!project.join(path).exists(), "`{path}` was not removed");
+	}
+
+	Ok(())
+}
+
+#[test]
+fn rollback_keeps_existing_directories() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/rollback_existing_dir");
+	reset_testing_dir(&out_dir);
+
+	// A directory that is in the way of a generated file is not created by the command
+	let license_dir = out_dir.join("LICENSE");
+	create_dir_all(&license_dir)?;
+	write_file(&license_dir.join("keep.txt"), "x\n", true)?;
+
+	let output = get_bin!()
+		.current_dir(&out_dir)
+		.args(["--ignore-config", "license", "mit", "-o", "LICENSE"])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	assert_eq!(read_to_string(license_dir.join("keep.txt"))?, "x\n");
+
+	Ok(())
+}
@@ -0,0 +1,216 @@
+use super::*;
+
+const CONFIG: &str = indoc! {r##"
+	templates:
+	  readme: "# {{ project_name }}\n\n{{ description }}\n"
+
+	gitignore_presets:
+	  node:
+	    content:
+	      - node_modules
+
+	templating_presets:
+	  readme:
+	    context:
+	      description: A new project
+	    templates:
+	      - template: readme
+	        output: README.md
+
+	repo_presets:
+	  app:
+	    gitignore: node
+	    with_templates:
+	      - preset_id: readme
+"##};
+
+fn update(config: &Path, project: &Path, extra_args: &[&str]) -> std::process::Output {
+	get_bin!()
+		.args(["-c", path_to_str!(config), "update"])
+		.args(extra_args)
+		.arg(project)
+		.output()
+		.expect("Failed to run the update command")
+}
+
+#[test]
+fn update_command() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/update");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+
+	write_file(&config, CONFIG, true)?;
+
+	get_bin!()
+		.args([
+			"-c",
+			path_to_str!(config),
+			"--set",
+			"project_name=\"my-project\"",
+			"repo",
+			"--preset",
+			"app",
+			path_to_str!(project),
+		])
+		.assert()
+		.success();
+
+	// Local changes
+	write_file(
+		&project.join("README.md"),
+		"# my-project\n\nA new project\n\n## Local notes\n",
+		true,
+	)?;
+	write_file(&project.join(".gitignore"), "node_modules/\n", true)?;
+
+	// Preset changes
+	write_file(
+		&config,
+		&CONFIG
+			.replace("# {{ project_name }}", "# The {{ project_name }} project")
+			.replace("- node_modules", "- dist"),
+		true,
+	)?;
+
+	let output = update(&config, &project, &[]);
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(stdout.contains("Merged the changes into `README.md`"));
+	assert!(stdout.contains("Conflicts in `.gitignore`"));
+
+	// The vars from the original generation are reused
+	assert_eq!(
+		read_to_string(project.join("README.md"))?,
+		"# The my-project project\n\nA new project\n\n## Local notes\n"
+	);
+
+	assert_eq!(
+		read_to_string(project.join(".gitignore"))?,
+		"<<<<<<< current\nnode_modules/\n=======\ndist\n>>>>>>> generated\n"
+	);
+
+	// The new output becomes the base for the next update
+	let manifest = GenerationManifest::from_dir(&project)?.expect("Manifest not found");
+
+	assert_eq!(
+		manifest
+			.get_entry(Path::new(".gitignore"))
+			.and_then(|entry| entry.content.as_deref()),
+		Some("dist".as_bytes())
+	);
+
+	// Rejected changes
+	write_file(&project.join(".gitignore"), "dist/\n", true)?;
+	write_file(&config, &CONFIG.replace("- node_modules", "- build"), true)?;
+
+	let output = update(&config, &project, &["--reject"]);
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(stdout.contains("Some changes could not be applied to `.gitignore`"));
+	assert!(stdout.contains("Merged the changes into `README.md`"));
+
+	assert_eq!(read_to_string(project.join(".gitignore"))?, "dist/\n");
+
+	let rejected = read_to_string(project.join(".gitignore.rej"))?;
+
+	assert!(rejected.contains("-dist\n"), "{rejected}");
+	assert!(rejected.contains("+build\n"), "{rejected}");
+
+	Ok(())
+}
+
+#[test]
+fn update_without_manifest() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/update_without_manifest");
+	reset_testing_dir(&out_dir);
+
+	let output = get_bin!()
+		.args(["--ignore-config", "update"])
+		.arg(&out_dir)
+		.output()?;
+
+	assert!(!output.status.success());
+	assert!(String::from_utf8(output.stderr)?.contains("Could not find a generation manifest"));
+
+	Ok(())
+}
+
+#[test]
+fn update_with_overwrite_policies() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/update_with_overwrite_policies");
+	reset_testing_dir(&out_dir);
+
+	let config = out_dir.join("sketch.yaml");
+	let project = out_dir.join("project");
+	let readme = project.join("README.md");
+
+	let config_with_rule = format!("{CONFIG}\noverwrite_rules:\n  README.md: skip\n");
+
+	write_file(&config, &config_with_rule, true)?;
+	create_dir_all(&project)?;
+	write_file(&readme, "# My own readme\n", true)?;
+
+	get_bin!()
+		.args([
+			"-c",
+			path_to_str!(config),
+			"--set",
+			"project_name=\"my-project\"",
+			"repo",
+			"--preset",
+			"app",
+			path_to_str!(project),
+		])
+		.assert()
+		.success();
+
+	// The skipped file was not generated, so it is not recorded
+	let manifest = GenerationManifest::from_dir(&project)?.expect("Manifest not found");
+
+	assert!(
+		manifest
+			.get_entry(Path::new("README.md"))
+			.is_none()
+	);
+	assert!(
+		manifest
+			.get_entry(Path::new(".gitignore"))
+			.is_some()
+	);
+
+	write_file(
+		&config,
+		&config_with_rule.replace("# {{ project_name }}", "# The {{ project_name }} project"),
+		true,
+	)?;
+
+	let output = update(&config, &project, &[]);
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(
+		stdout.contains("README.md`, since it already exists"),
+		"{stdout}"
+	);
+	assert_eq!(read_to_string(&readme)?, "# My own readme\n");
+
+	// Without the rule, the file of the user is never used as the base, so it is not silently overwritten
+	write_file(
+		&config,
+		&CONFIG.replace("# {{ project_name }}", "# The {{ project_name }} project"),
+		true,
+	)?;
+
+	let output = update(&config, &project, &[]);
+	let stdout = String::from_utf8(output.stdout)?;
+
+	assert!(output.status.success(), "{stdout}");
+	assert!(stdout.contains("Conflicts in `README.md`"), "{stdout}");
+	assert!(read_to_string(&readme)?.contains("# My own readme\n"));
+
+	Ok(())
+}
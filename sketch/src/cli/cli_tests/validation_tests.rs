@@ -0,0 +1,34 @@
+use super::*;
+
+#[tokio::test]
+async fn config_validation() -> Result<(), Box<dyn std::error::Error>> {
+	let config = Config::from_file("tests/validation/sketch.yaml")?;
+
+	let issues = match config.validate() {
+		Err(AppError::InvalidConfig(issues)) => issues,
+		other => panic!("Expected validation errors, got {other:?}"),
+	};
+
+	let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+
+	let expected = [
+		"`templating_presets.first`: Found circular Templates dependency for preset with id 'first'. The full processed chain is: first -> second -> third -> first",
+		"`gitignore_presets.with_missing_base`: Gitignore preset `missing_gitignore` not found",
+		"`repo_presets.repo`: Gitignore preset `missing_gitignore` not found",
+		"`repo_presets.repo`: PreCommit preset `missing_pre_commit` not found",
+		"`repo_presets.repo`: Templates preset `missing_templating_preset` not found",
+		"`repo_presets.repo`: GithubWorkflow preset `missing_workflow` not found",
+		"`rust.crate_presets.my_crate`: CargoToml preset `missing_manifest` not found",
+		"`docker.compose_presets.compose`: DockerService preset `missing_service` not found",
+		"`github.workflow_presets.workflow`: GithubWorkflowJob preset `missing_job` not found",
+		"`github.workflow_job_presets.job`: GithubWorkflowStep preset `missing_step` not found",
+	];
+
+	pretty_assert_eq!(messages, expected);
+
+	let valid_config = Config::from_file(examples_dir().join("presets.yaml"))?;
+
+	valid_config.validate()?;
+
+	Ok(())
+}
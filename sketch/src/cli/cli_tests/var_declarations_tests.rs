@@ -0,0 +1,109 @@
+use super::*;
+
+#[tokio::test]
+async fn var_declarations() -> Result<(), Box<dyn std::error::Error>> {
+	let out_dir = PathBuf::from("tests/output/var_declarations");
+
+	if out_dir.exists() {
+		remove_dir_all(&out_dir)?;
+	}
+
+	get_bin!()
+		.args([
+			"-c",
+			"tests/var_declarations/sketch.yaml",
+			"render",
+			"--preset",
+			"license",
+			out_dir.to_str().unwrap(),
+		])
+		.assert()
+		.success();
+
+	// Default values are used for the vars that are not set
+	assert_eq!(
+		read_to_string(out_dir.join("LICENSE"))?,
+		"MIT - my-project (2025)"
+	);
+
+	get_bin!()
+		.args([
+			"-c",
+			"tests/var_declarations/sketch.yaml",
+			"--set",
+			"year=2030",
+			"render",
+			"--preset",
+			"license",
+			out_dir.to_str().unwrap(),
+		])
+		.assert()
+		.success();
+
+	assert_eq!(
+		read_to_string(out_dir.join("LICENSE"))?,
+		"MIT - my-project (2030)"
+	);
+
+	remove_file(out_dir.join("LICENSE"))?;
+
+	let output = get_bin!()
+		.args([
+			"-c",
+			"tests/var_declarations/sketch.yaml",
+			"--set",
+			"project_name=\"My Project\"",
+			"--set",
+			"year=\"2025\"",
+			"render",
+			"--preset",
+			"invalid",
+			out_dir.to_str().unwrap(),
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	// All the issues are reported at once
+	for issue in [
+		"Found 5 invalid variable(s):",
+		"`project_name`: the value `My Project` does not match the regex `^[a-z][a-z0-9-]*$`",
+		"`year`: expected a value of type integer, found string",
+		"`license` (templating preset `invalid`): must be one of [ \"MIT\", \"Apache-2.0\" ], found \"GPL-3.0\"",
+		"`version` (templating preset `invalid`): expected a value of type number, found string",
+		"`owner` (templating preset `invalid`): is required but was not set",
+	] {
+		assert!(stderr.contains(issue), "{stderr}");
+	}
+
+	// Nothing is rendered if there are invalid vars
+	assert!(!out_dir.join("LICENSE").exists());
+
+	// The same checks apply to the commands
+	let output = get_bin!()
+		.args([
+			"-c",
+			"tests/var_declarations/sketch.yaml",
+			"--set",
+			"year=\"2025\"",
+			"exec",
+			"--cwd",
+			out_dir.to_str().unwrap(),
+			"echo {{ year }} > year.txt",
+		])
+		.output()?;
+
+	assert!(!output.status.success());
+
+	let stderr = String::from_utf8(output.stderr)?;
+
+	assert!(
+		stderr.contains("`year`: expected a value of type integer, found string"),
+		"{stderr}"
+	);
+	assert!(!out_dir.join("year.txt").exists());
+
+	Ok(())
+}
@@ -0,0 +1,85 @@
+use std::io::Write as _;
+
+use clap_complete::{
+	ArgValueCandidates, CompletionCandidate,
+	env::{Bash, EnvCompleter, Fish, Zsh},
+};
+
+use super::*;
+
+/// The environment variable that activates the completion logic when the registration script calls `sketch`. It must match the default of `CompleteEnv`.
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// The shells that completions can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+	Bash,
+	Zsh,
+	Fish,
+}
+
+impl CompletionShell {
+	const fn completer(self) -> &'static dyn EnvCompleter {
+		match self {
+			Self::Bash => &Bash,
+			Self::Zsh => &Zsh,
+			Self::Fish => &Fish,
+		}
+	}
+}
+
+/// Prints the script that registers the completions for a shell.
+///
+/// The script calls `sketch` to get the completions, so that the preset ids are always taken from the current config.
+pub(crate) fn print_completions(shell: CompletionShell) -> AppResult {
+	// The path of the binary is resolved like in `clap_complete`, so that the script works when `sketch` is not in the PATH
+	let completer = env::args_os()
+		.next()
+		.map(PathBuf::from)
+		.filter(|path| path.components().count() > 1)
+		.map_or_else(
+			|| Ok("sketch".to_string()),
+			|path| get_abs_path(&path).map(|path| path.to_string_lossy().into_owned()),
+		)?;
+
+	let mut script = Vec::new();
+
+	shell
+		.completer()
+		.write_registration(COMPLETE_VAR, "sketch", "sketch", &completer, &mut script)
+		.context("Failed to generate the completions")?;
+
+	std::io::stdout()
+		.write_all(&script)
+		.context("Failed to print the completions")?;
+
+	Ok(())
+}
+
+/// Completes the ids of the presets of a given kind, with the config files detected from the cwd.
+pub(crate) fn preset_candidates(kind: PresetKind) -> ArgValueCandidates {
+	ArgValueCandidates::new(move || {
+		let config = get_config_from_defaults().and_then(|mut config| {
+			if let Some(profile) = env::var("SKETCH_PROFILE")
+				.ok()
+				.filter(|p| !p.is_empty())
+			{
+				config.apply_profile(&profile)?;
+			}
+
+			Ok(config)
+		});
+
+		// Completions should never fail, so invalid configs simply offer no candidates
+		config.map_or_else(
+			|_| Vec::new(),
+			|config| {
+				config
+					.preset_ids(kind)
+					.into_iter()
+					.map(CompletionCandidate::new)
+					.collect()
+			},
+		)
+	})
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommands {
+	/// Resolves every preset in the config (along with the presets that they extend or reference) and reports all the errors found.
+	Validate,
+
+	/// Serializes the fully merged config (including the values set via cli, `--vars-file` and `--set`) so that it can be inspected or used as a config file.
+	Dump {
+		/// The output format. If unset, it is inferred from the output file, falling back to yaml.
+		#[arg(short, long, value_enum)]
+		format: Option<Format>,
+
+		/// The output file. If unset, the config is printed to stdout.
+		output: Option<PathBuf>,
+	},
+
+	/// Rewrites a config file that uses an older format so that it uses the current one. The file keeps the same format, but comments are not preserved.
+	Migrate {
+		/// The config file to migrate. Defaults to the `sketch.{yaml,json,toml}` file in the cwd.
+		file: Option<PathBuf>,
+	},
+}
+
+impl ConfigCommands {
+	/// Whether the command can be executed without loading the config (which may fail if the config must be migrated).
+	pub(crate) const fn needs_config(&self) -> bool {
+		!matches!(self, Self::Migrate { .. })
+	}
+
+	pub fn execute(self, config: &Config, cli_vars: &IndexMap<String, Value>) -> AppResult {
+		match self {
+			Self::Validate => {
+				config.validate()?;
+
+				emit_message("No errors found in the config");
+			}
+			Self::Dump { format, output } => {
+				let dump = config.to_dump(cli_vars);
+
+				if let Some(output) = output {
+					let format = match format {
+						Some(format) => format,
+						None => Format::from_path(&output).unwrap_or(Format::Yaml),
+					};
+
+					create_parent_dirs(&output)?;
+
+					format.serialize_to_file(&dump, &output, config.can_overwrite())?;
+				} else {
+					let output = format.unwrap_or(Format::Yaml).serialize(&dump)?;
+
+					emit(OutputEvent::Output {
+						content: output.trim_end().to_string(),
+					});
+				}
+			}
+			Self::Migrate { file } => {
+				let file = file
+					.or_else(find_config_in_cwd)
+					.context("No config file found in the cwd")?;
+
+				let report = migrate_config_file(&file)?;
+
+				emit_message(format!(
+					"Migrated `{}` from {} to v{CONFIG_VERSION}",
+					file.display(),
+					report
+						.from_version
+						.map_or_else(|| "an unversioned config".to_string(), |v| format!("v{v}"))
+				));
+
+				for change in &report.changes {
+					emit_message(format!("  - {change}"));
+				}
+
+				if !report.removed.is_empty() {
+					emit_message(
+						"The following items are no longer supported and have been removed, so they must be migrated manually:",
+					);
+
+					for item in &report.removed {
+						emit_message(format!("  - {item}"));
+					}
+				}
+			}
+		};
+
+		Ok(())
+	}
+}
@@ -7,24 +7,35 @@ pub(crate) fn get_config_from_cli(
 	let ConfigOverrides {
 		templates_dir,
 		no_overwrite,
+		overwrite,
+		no_input,
 		config: config_path,
 		ignore_config,
+		profile,
 	} = overrides;
 
-	let config_path = if let Some(path) = config_path {
-		Some(path)
-	} else if !ignore_config {
-		get_config_path_from_defaults()
-	} else {
-		None
-	};
+	if let Commands::Config { command } = command
+		&& !command.needs_config()
+	{
+		return Ok(Config::default());
+	}
+
+	if let Commands::Completions { .. } | Commands::Import { .. } = command {
+		return Ok(Config::default());
+	}
 
 	let mut config = if let Some(config_path) = config_path {
 		Config::from_file(&config_path)?
+	} else if !ignore_config {
+		get_config_from_defaults()?
 	} else {
 		Config::default()
 	};
 
+	if let Some(profile) = profile {
+		config.apply_profile(&profile)?;
+	}
+
 	if let Some(templates_dir) = templates_dir {
 		config.templates_dir = Some(templates_dir);
 	}
@@ -33,6 +44,14 @@ pub(crate) fn get_config_from_cli(
 		config.no_overwrite = true;
 	}
 
+	if let Some(overwrite) = overwrite {
+		config.overwrite = Some(overwrite);
+	}
+
+	if no_input {
+		config.no_input = true;
+	}
+
 	if let Commands::Ts {
 		typescript_overrides,
 		..
@@ -50,15 +69,82 @@ pub(crate) fn get_config_from_cli(
 
 const DEFAULT_CONFIG_NAMES: [&str; 3] = ["sketch.yaml", "sketch.toml", "sketch.json"];
 
-fn get_config_path_from_defaults() -> Option<PathBuf> {
-	for name in DEFAULT_CONFIG_NAMES {
-		if exists(name).is_ok_and(|exists| exists) {
-			return Some(PathBuf::from(name));
+pub(super) fn get_config_from_defaults() -> AppResult<Config> {
+	let cwd = get_cwd();
+
+	Ok(
+		get_hierarchical_config(&cwd, env::home_dir().as_deref(), get_config_from_xdg())?
+			.unwrap_or_default(),
+	)
+}
+
+pub(crate) fn find_config_in_cwd() -> Option<PathBuf> {
+	find_config_in_dir(&get_cwd())
+}
+
+fn find_config_in_dir(dir: &Path) -> Option<PathBuf> {
+	DEFAULT_CONFIG_NAMES
+		.iter()
+		.map(|name| dir.join(name))
+		.find(|path| exists(path).is_ok_and(|exists| exists))
+}
+
+/// Collects the config files found in `start_dir` and its ancestors (up to the filesystem root, the home directory or a config marked as `root`), and merges them, starting from the XDG config (if present) and ending with the closest config file.
+pub(crate) fn get_hierarchical_config(
+	start_dir: &Path,
+	home_dir: Option<&Path>,
+	xdg_config: Option<PathBuf>,
+) -> AppResult<Option<Config>> {
+	let mut configs: Vec<Config> = Vec::new();
+
+	for dir in start_dir.ancestors() {
+		if let Some(config_path) = find_config_in_dir(dir) {
+			let config = Config::from_file(&config_path)?;
+			let is_root = config.root;
+
+			configs.push(config);
+
+			if is_root {
+				break;
+			}
 		}
+
+		if home_dir.is_some_and(|home| home == dir) {
+			break;
+		}
+	}
+
+	if let Some(xdg_config) = xdg_config {
+		let xdg_config = get_abs_path(&xdg_config)?;
+
+		// The XDG config might have already been found while walking up the tree
+		if !configs
+			.iter()
+			.any(|config| config.config_file.as_ref() == Some(&xdg_config))
+		{
+			configs.push(Config::from_file(xdg_config)?);
+		}
+	}
+
+	// From the lowest to the highest priority
+	let mut layers = configs.into_iter().rev();
+
+	let Some(mut config) = layers.next() else {
+		return Ok(None);
+	};
+
+	for layer in layers {
+		let config_file = layer.config_file.clone();
+		let extends = layer.extends.clone();
+
+		config.merge(layer);
+
+		// Keeping the data of the closest config file
+		config.config_file = config_file;
+		config.extends = extends;
 	}
 
-	// Try xdg path if nothing else was found
-	get_config_from_xdg()
+	Ok(Some(config))
 }
 
 fn get_config_from_xdg() -> Option<PathBuf> {
@@ -0,0 +1,492 @@
+use serde::de::DeserializeOwned;
+use serde_json::Map;
+use serde_yaml_ng::{Mapping, Value as YamlValue};
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::*;
+use crate::{
+	config::preset_map_path,
+	docker::ComposeFile,
+	gh_workflow::Workflow,
+	init_repo::pre_commit::PreCommitConfig,
+	rust::Manifest,
+	ts::{package_json::PackageJson, ts_config::TsConfig},
+};
+
+impl Cli {
+	pub(super) fn import_preset(
+		file: &Path,
+		id: &str,
+		kind: Option<PresetKind>,
+		config_file: Option<PathBuf>,
+	) -> AppResult {
+		let kind = match kind {
+			Some(kind) => kind,
+			None => detect_preset_kind(file).with_context(|| {
+				format!(
+					"Could not detect the kind of preset for `{}`. It can be set with `--kind`",
+					file.display()
+				)
+			})?,
+		};
+
+		let config_file = config_file
+			.or_else(find_config_in_cwd)
+			.context("No config file found in the cwd. It can be selected with `--into`")?;
+
+		let preset = parse_preset(file, kind)?;
+		let key_path = preset_map_path(kind);
+
+		let content = read_file_to_string(&config_file)?;
+
+		let new_content = match Format::from_path(&config_file)? {
+			Format::Yaml => insert_yaml_preset(&content, key_path, id, &preset)?,
+			Format::Toml => insert_toml_preset(&content, key_path, id, &preset)?,
+			Format::Json => insert_json_preset(&content, key_path, id, &preset)?,
+		}
+		.ok_or_else(|| {
+			anyhow!(
+				"The {kind:?} preset `{id}` already exists in `{}`",
+				config_file.display()
+			)
+		})?;
+
+		// Making sure that the result is still a valid config, before touching the original file
+		check_config_content(&config_file, &new_content)?;
+
+		// The new content already includes the existing one, so the overwrite policies do not apply here
+		without_overwrite_policies(|| write_file(&config_file, &new_content, true))?;
+
+		emit_message(format!(
+			"Imported `{}` as the {kind:?} preset `{id}` in `{}`",
+			file.display(),
+			config_file.display()
+		));
+
+		Ok(())
+	}
+}
+
+/// Detects the kind of preset from the name of a file.
+fn detect_preset_kind(file: &Path) -> Option<PresetKind> {
+	let name = file.file_name()?.to_str()?;
+
+	let is_yaml = name.ends_with(".yaml") || name.ends_with(".yml");
+
+	let kind = match name {
+		"Cargo.toml" => PresetKind::CargoToml,
+		"package.json" => PresetKind::PackageJson,
+		".pre-commit-config.yaml" | ".pre-commit-config.yml" => PresetKind::PreCommit,
+		"compose.yaml" | "compose.yml" | "docker-compose.yaml" | "docker-compose.yml" => {
+			PresetKind::ComposeFile
+		}
+		_ if name.starts_with("tsconfig") && name.ends_with(".json") => PresetKind::TsConfig,
+		_ if is_yaml
+			&& file
+				.parent()
+				.and_then(Path::file_name)
+				.is_some_and(|dir| dir == "workflows") =>
+		{
+			PresetKind::GithubWorkflow
+		}
+		_ => return None,
+	};
+
+	Some(kind)
+}
+
+/// Parses a file with the type of its preset, and returns the preset as a generic value.
+fn parse_preset(file: &Path, kind: PresetKind) -> AppResult<Value> {
+	let content = read_file_to_string(file)?;
+
+	let format = match kind {
+		PresetKind::CargoToml => validate_preset::<Manifest>(file, &content, Format::Toml)?,
+		PresetKind::PackageJson => validate_preset::<PackageJson>(file, &content, Format::Json)?,
+		PresetKind::TsConfig => validate_preset::<TsConfig>(file, &content, Format::Json)?,
+		PresetKind::ComposeFile => validate_preset::<ComposeFile>(file, &content, Format::Yaml)?,
+		PresetKind::GithubWorkflow => validate_preset::<Workflow>(file, &content, Format::Yaml)?,
+		PresetKind::PreCommit => validate_preset::<PreCommitConfig>(file, &content, Format::Yaml)?,
+		_ => return Err(anyhow!("{kind:?} presets cannot be imported from a file").into()),
+	};
+
+	// The raw content is imported rather than the typed version, which would also contain the default values of the fields that are not in the file
+	let mut preset: Value = match format {
+		// Unlike the toml parser used for the config files, this one keeps the keys in the same order as in the file
+		Format::Toml => toml_edit::de::from_str(&content)
+			.with_context(|| format!("Failed to parse `{}`", file.display()))?,
+		_ => format.deserialize(file, &content)?,
+	};
+
+	// The `null` values would fail to serialize in toml
+	remove_nulls(&mut preset);
+
+	Ok(preset)
+}
+
+/// Checks that the content of a file is valid for the type of its preset, and returns the format to parse it with.
+fn validate_preset<T: DeserializeOwned>(
+	file: &Path,
+	content: &str,
+	format: Format,
+) -> AppResult<Format> {
+	format.deserialize::<T>(file, content)?;
+
+	Ok(format)
+}
+
+fn check_config_content(config_file: &Path, content: &str) -> AppResult {
+	let result = match Format::from_path(config_file)? {
+		Format::Yaml => serde_yaml_ng::from_str::<Config>(content).map_err(|e| e.to_string()),
+		Format::Toml => toml::from_str::<Config>(content).map_err(|e| e.to_string()),
+		Format::Json => serde_json::from_str::<Config>(content).map_err(|e| e.to_string()),
+	};
+
+	result.map(|_| ()).map_err(|error| {
+		anyhow!(
+			"The imported preset would make `{}` invalid: {error}",
+			config_file.display()
+		)
+		.into()
+	})
+}
+
+/// Adds a preset to a toml config. Returns `None` if the preset already exists.
+fn insert_toml_preset(
+	content: &str,
+	key_path: &[&str],
+	id: &str,
+	preset: &Value,
+) -> AppResult<Option<String>> {
+	let mut document = content
+		.parse::<DocumentMut>()
+		.context("Failed to parse the config file")?;
+
+	let mut table = document.as_table_mut();
+
+	for key in key_path {
+		let item = table.entry(key).or_insert_with(|| {
+			let mut new_table = Table::new();
+			new_table.set_implicit(true);
+			Item::Table(new_table)
+		});
+
+		table = item
+			.as_table_mut()
+			.with_context(|| format!("`{key}` in the config file is not a table"))?;
+	}
+
+	if table.contains_key(id) {
+		return Ok(None);
+	}
+
+	let preset_document = toml_edit::ser::to_document(preset)
+		.map_err(|e| anyhow!("Could not serialize the preset to toml: {e}"))?;
+
+	table.insert(id, Item::Table(preset_document.as_table().clone()));
+
+	Ok(Some(document.to_string()))
+}
+
+/// Adds a preset to a json config, by inserting it in the existing content so that the formatting is preserved. Returns `None` if the preset already exists.
+fn insert_json_preset(
+	content: &str,
+	key_path: &[&str],
+	id: &str,
+	preset: &Value,
+) -> AppResult<Option<String>> {
+	let config: Value = serde_json::from_str(content).context("Failed to parse the config file")?;
+
+	let mut map = config
+		.as_object()
+		.context("The config file is not a json object")?;
+	let mut found_keys = 0;
+
+	for key in key_path {
+		let Some(value) = map.get(*key) else {
+			break;
+		};
+
+		found_keys += 1;
+
+		map = value.as_object().with_context(|| {
+			format!(
+				"`{}` in the config file is not an object",
+				key_path[..found_keys].join(".")
+			)
+		})?;
+	}
+
+	if found_keys == key_path.len() && map.contains_key(id) {
+		return Ok(None);
+	}
+
+	let bytes = content.as_bytes();
+
+	let open = find_json_object(content, &key_path[..found_keys])?;
+	let close = skip_json_value(bytes, open) - 1;
+
+	let line_start = content[..open].rfind('\n').map_or(0, |i| i + 1);
+	let line = &content[line_start..];
+	let indent = &line[..line.len() - line.trim_start().len()];
+
+	let indent_unit = content
+		.lines()
+		.find_map(|line| {
+			let indent_len = line.len() - line.trim_start().len();
+			(indent_len > 0).then(|| &line[..indent_len])
+		})
+		.unwrap_or("  ");
+
+	let child_indent = format!("{indent}{indent_unit}");
+
+	// The maps that do not exist yet are created around the preset
+	let mut entry_key = id;
+	let mut entry_value = preset.clone();
+
+	for key in key_path[found_keys..].iter().rev() {
+		entry_value = Value::Object(Map::from_iter([(entry_key.to_string(), entry_value)]));
+		entry_key = key;
+	}
+
+	let mut output = Vec::new();
+	let formatter = serde_json::ser::PrettyFormatter::with_indent(indent_unit.as_bytes());
+	let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+
+	entry_value
+		.serialize(&mut serializer)
+		.context("Failed to serialize the preset")?;
+
+	let entry_value = String::from_utf8(output).context("Invalid utf8 in the preset")?;
+
+	let entry = format!(
+		"{}: {}",
+		serde_json::to_string(entry_key).context("Failed to serialize the preset id")?,
+		entry_value.replace('\n', &format!("\n{child_indent}"))
+	);
+
+	let members_end = content[..close].trim_end().len();
+
+	let output = if members_end == open + 1 {
+		format!(
+			"{}\n{child_indent}{entry}\n{indent}{}",
+			&content[..=open],
+			&content[close..]
+		)
+	} else {
+		format!(
+			"{},\n{child_indent}{entry}{}",
+			&content[..members_end],
+			&content[members_end..]
+		)
+	};
+
+	Ok(Some(output))
+}
+
+/// Returns the position of the opening brace of the object at a key path, in a valid json document.
+fn find_json_object(content: &str, key_path: &[&str]) -> AppResult<usize> {
+	let bytes = content.as_bytes();
+
+	let mut open = skip_json_whitespace(bytes, 0);
+
+	for key in key_path {
+		let mut pos = open + 1;
+
+		loop {
+			pos = skip_json_whitespace(bytes, pos);
+
+			let key_end = skip_json_value(bytes, pos);
+			let member_key: String = serde_json::from_str(&content[pos..key_end])
+				.context("Failed to parse the config file")?;
+
+			// Skipping the colon
+			pos = skip_json_whitespace(bytes, skip_json_whitespace(bytes, key_end) + 1);
+
+			if member_key == *key {
+				open = pos;
+				break;
+			}
+
+			// Skipping the value and the comma that follows it
+			pos = skip_json_whitespace(bytes, skip_json_value(bytes, pos)) + 1;
+		}
+	}
+
+	Ok(open)
+}
+
+fn skip_json_whitespace(bytes: &[u8], pos: usize) -> usize {
+	bytes[pos..]
+		.iter()
+		.position(|byte| !byte.is_ascii_whitespace())
+		.map_or(bytes.len(), |offset| pos + offset)
+}
+
+/// Returns the position right after the json value that starts at the given position.
+fn skip_json_value(bytes: &[u8], pos: usize) -> usize {
+	match bytes[pos] {
+		b'"' => {
+			let mut i = pos + 1;
+
+			while i < bytes.len() {
+				match bytes[i] {
+					b'\\' => i += 2,
+					b'"' => return i + 1,
+					_ => i += 1,
+				}
+			}
+
+			bytes.len()
+		}
+		b'{' | b'[' => {
+			let mut depth = 0;
+			let mut i = pos;
+
+			while i < bytes.len() {
+				match bytes[i] {
+					b'"' => {
+						i = skip_json_value(bytes, i);
+						continue;
+					}
+					b'{' | b'[' => depth += 1,
+					b'}' | b']' => {
+						depth -= 1;
+
+						if depth == 0 {
+							return i + 1;
+						}
+					}
+					_ => {}
+				}
+
+				i += 1;
+			}
+
+			bytes.len()
+		}
+		_ => bytes[pos..]
+			.iter()
+			.position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())
+			.map_or(bytes.len(), |offset| pos + offset),
+	}
+}
+
+/// Adds a preset to a yaml config, by inserting the new lines in the existing content so that the comments and the formatting are preserved. Returns `None` if the preset already exists.
+fn insert_yaml_preset(
+	content: &str,
+	key_path: &[&str],
+	id: &str,
+	preset: &Value,
+) -> AppResult<Option<String>> {
+	let config: YamlValue =
+		serde_yaml_ng::from_str(content).context("Failed to parse the config file")?;
+
+	let mut node = Some(&config);
+
+	for key in key_path {
+		node = node.and_then(|node| node.get(key));
+	}
+
+	if node.is_some_and(|presets| presets.get(id).is_some()) {
+		return Ok(None);
+	}
+
+	let mut lines: Vec<String> = content.lines().map(ToString::to_string).collect();
+
+	let indent_unit = lines
+		.iter()
+		.map(|line| indentation(line))
+		.filter(|indent| *indent > 0)
+		.min()
+		.unwrap_or(2);
+
+	// The range of lines that contains the entries of the current map
+	let mut start = 0;
+	let mut end = lines.len();
+	let mut child_indent = 0;
+	let mut missing_keys = key_path;
+
+	while let Some((key, rest)) = missing_keys.split_first() {
+		let Some(key_line) = (start..end).find(|i| {
+			indentation(&lines[*i]) == child_indent && yaml_key(&lines[*i]) == Some(*key)
+		}) else {
+			break;
+		};
+
+		let inline_value = lines[key_line]
+			.split_once(':')
+			.map_or("", |(_, value)| value.trim());
+
+		// An empty map in the flow style is replaced with a block
+		if inline_value == "{}" || inline_value == "{ }" {
+			lines[key_line] = format!("{}{key}:", " ".repeat(child_indent));
+		} else if !inline_value.is_empty() && !inline_value.starts_with('#') {
+			return Err(anyhow!(
+				"`{}` in the config file must be a block mapping, so that the preset can be added to it",
+				key_path.join(".")
+			)
+			.into());
+		}
+
+		start = key_line + 1;
+		end = (start..end)
+			.find(|i| is_yaml_content(&lines[*i]) && indentation(&lines[*i]) <= child_indent)
+			.unwrap_or(end);
+		child_indent = (start..end)
+			.find(|i| is_yaml_content(&lines[*i]))
+			.map_or(child_indent + indent_unit, |i| indentation(&lines[i]));
+		missing_keys = rest;
+	}
+
+	let mut new_lines = Vec::new();
+
+	for key in missing_keys {
+		new_lines.push(format!("{}{key}:", " ".repeat(child_indent)));
+		child_indent += indent_unit;
+	}
+
+	let mut entry = Mapping::new();
+	entry.insert(
+		YamlValue::String(id.to_string()),
+		serde_yaml_ng::to_value(preset).context("Failed to convert the preset to yaml")?,
+	);
+
+	let entry = serde_yaml_ng::to_string(&entry).context("Failed to serialize the preset")?;
+
+	new_lines.extend(
+		entry
+			.lines()
+			.map(|line| format!("{}{line}", " ".repeat(child_indent))),
+	);
+
+	// The new lines are added after the last entry of the map, before the blank lines and the comments that follow it
+	let insert_at = (start..end)
+		.rev()
+		.find(|i| is_yaml_content(&lines[*i]))
+		.map_or(start, |i| i + 1);
+
+	lines.splice(insert_at..insert_at, new_lines);
+
+	let mut output = lines.join("\n");
+	output.push('\n');
+
+	Ok(Some(output))
+}
+
+fn indentation(line: &str) -> usize {
+	line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether a line contains a value, rather than being blank or a comment.
+fn is_yaml_content(line: &str) -> bool {
+	let trimmed = line.trim();
+
+	!trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// The key defined in a line, if there is one.
+fn yaml_key(line: &str) -> Option<&str> {
+	let (key, _) = line.trim().split_once(':')?;
+
+	Some(key.trim_matches(|c| c == '"' || c == '\''))
+}
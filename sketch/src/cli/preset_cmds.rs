@@ -0,0 +1,50 @@
+use super::*;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PresetCommands {
+	/// Resolves two presets of the same kind (along with the presets that they extend) and prints the values that were added, removed or changed in the second one, by key path.
+	Diff {
+		/// The kind of the presets.
+		#[arg(value_enum)]
+		kind: PresetKind,
+
+		/// The id of the first preset.
+		a: String,
+
+		/// The id of the second preset.
+		b: String,
+	},
+
+	/// Resolves a preset and prints each of its values along with the id of the preset that set it, as well as the order in which the extended presets are merged.
+	Explain {
+		/// The kind of the preset.
+		#[arg(value_enum)]
+		kind: PresetKind,
+
+		/// The preset id.
+		id: String,
+	},
+}
+
+impl PresetCommands {
+	pub fn execute(self, config: &Config) -> AppResult {
+		match self {
+			Self::Diff { kind, a, b } => {
+				let changes = config.diff_presets(kind, &a, &b)?;
+
+				if changes.is_empty() {
+					emit_message(format!("The presets `{a}` and `{b}` are identical"));
+				}
+
+				emit(OutputEvent::PresetDiff { changes });
+			}
+			Self::Explain { kind, id } => {
+				let explanation = config.explain_preset(kind, &id)?;
+
+				emit(OutputEvent::PresetExplanation(explanation));
+			}
+		};
+
+		Ok(())
+	}
+}
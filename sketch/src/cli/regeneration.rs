@@ -0,0 +1,115 @@
+use super::*;
+
+/// Reads the generation manifest of a project that should be generated again.
+pub(super) fn read_generation_manifest(dir: &Path) -> AppResult<GenerationManifest> {
+	GenerationManifest::from_dir(dir)?.ok_or_else(|| {
+		anyhow!(
+			"Could not find a generation manifest in `{}`. Only directories generated with the `repo`, `rust crate`, `ts package` and `ts monorepo` commands can be generated again",
+			dir.display()
+		)
+		.into()
+	})
+}
+
+impl GenerationCommand {
+	/// The cli command that generates the files in `out_dir`. The dependencies are never installed.
+	pub(crate) fn into_cli_command(self, out_dir: PathBuf) -> Commands {
+		match self {
+			Self::Repo { preset } => Commands::Repo {
+				dir: Some(out_dir),
+				preset,
+				overrides: None,
+				remote: None,
+			},
+			Self::RustCrate {
+				preset,
+				manifest,
+				name,
+			} => Commands::Rust {
+				command: RustCommands::Crate {
+					dir: out_dir,
+					preset,
+					manifest,
+					name,
+					config: None,
+				},
+			},
+			Self::TsPackage { preset, vitest } => Commands::Ts {
+				typescript_overrides: None,
+				command: TsCommands::Package {
+					dir: Some(out_dir),
+					preset,
+					update_tsconfig: vec![],
+					install: false,
+					vitest,
+					package_config: None,
+				},
+			},
+			Self::TsMonorepo { root_package, pnpm } => Commands::Ts {
+				typescript_overrides: None,
+				command: TsCommands::Monorepo {
+					dir: out_dir,
+					pnpm,
+					root_package,
+					root_package_overrides: None,
+					install: false,
+				},
+			},
+		}
+	}
+}
+
+impl Cli {
+	/// Generates the files of a project again in dry-run mode, using the command and the variables recorded in its manifest.
+	///
+	/// Nothing is written to disk: the returned manifest holds the contents of the generated files.
+	pub(super) async fn regenerate_project(
+		dir: &Path,
+		previous: &GenerationManifest,
+		config: Config,
+		cli_vars: IndexMap<String, Value>,
+	) -> AppResult<GenerationManifest> {
+		let abs_dir = get_abs_path(dir)?;
+
+		let dir_name = abs_dir
+			.file_name()
+			.with_context(|| format!("Could not get the name of `{}`", abs_dir.display()))?;
+
+		// A virtual directory, which is never created
+		let tmp_root = env::temp_dir()
+			.join("sketch/regenerate")
+			.join(uuid::Uuid::new_v4().to_string());
+
+		// The workspace manifest affects the generated `Cargo.toml`
+		let workspace_manifest = abs_dir
+			.parent()
+			.map(|parent| parent.join("Cargo.toml"))
+			.filter(|path| path.is_file())
+			.map(|path| read_file_to_string(&path))
+			.transpose()?;
+
+		let out_dir = tmp_root.join(dir_name);
+
+		let command = previous
+			.command
+			.clone()
+			.into_cli_command(out_dir.clone());
+
+		let mut vars = previous.vars.clone();
+		vars.extend(cli_vars);
+
+		let (regenerated, _) = with_dry_run(async {
+			if let Some(workspace_manifest) = workspace_manifest {
+				write_file(&tmp_root.join("Cargo.toml"), &workspace_manifest, true)?;
+			}
+
+			Box::pin(Self::execute_command(command, config, vars)).await?;
+
+			GenerationManifest::from_dir(&out_dir)
+		})
+		.await;
+
+		Ok(regenerated?
+			.context("The generation manifest was not created for the regenerated files")?)
+	}
+}
@@ -7,11 +7,11 @@ pub enum RustCommands {
 		dir: PathBuf,
 
 		/// The crate preset to use.
-		#[arg(short, long)]
+		#[arg(short, long, add = preset_candidates(PresetKind::RustCrate))]
 		preset: Option<String>,
 
 		/// The `Cargo.toml` manifest preset to use (overrides the one in the preset if one was selected).
-		#[arg(short, long)]
+		#[arg(short, long, add = preset_candidates(PresetKind::CargoToml))]
 		manifest: Option<String>,
 
 		/// The name of the generated crate (by default, it uses the name of the output dir).
@@ -25,6 +25,7 @@ pub enum RustCommands {
 	/// Generates a new `Cargo.toml` file from a preset.
 	Manifest {
 		/// The id of the preset.
+		#[arg(add = preset_candidates(PresetKind::CargoToml))]
 		preset: String,
 
 		/// The output path [default: `Cargo.toml`]
@@ -53,8 +54,8 @@ impl RustCommands {
 				config: overrides,
 				manifest,
 			} => {
-				let mut preset = if let Some(preset_id) = preset_id {
-					config.rust.get_crate_preset(&preset_id)?
+				let mut preset = if let Some(preset_id) = &preset_id {
+					config.rust.get_crate_preset(preset_id)?
 				} else {
 					CratePreset::default()
 				};
@@ -63,8 +64,8 @@ impl RustCommands {
 					preset.merge(overrides);
 				}
 
-				if let Some(manifest_id) = manifest {
-					preset.manifest = CargoTomlPresetRef::PresetId(manifest_id);
+				if let Some(manifest_id) = &manifest {
+					preset.manifest = CargoTomlPresetRef::PresetId(manifest_id.clone());
 				}
 
 				let crate_data = preset.process_data(config)?;
@@ -73,7 +74,21 @@ impl RustCommands {
 					return Err(anyhow!("Directory `{}` already exists", dir.display()).into());
 				}
 
-				crate_data.generate(&dir, name, config)?;
+				let mut generation_manifest = GenerationManifest::new(
+					&dir,
+					GenerationCommand::RustCrate {
+						preset: preset_id,
+						manifest,
+						name: name.clone(),
+					},
+					&IndexMap::new(),
+				);
+
+				with_preset_policy_sync(crate_data.overwrite, || {
+					crate_data.generate(&dir, name, config, &mut generation_manifest)
+				})?;
+
+				generation_manifest.write()?;
 			}
 		};
 
@@ -24,11 +24,11 @@ pub enum TsCommands {
 		dir: PathBuf,
 
 		/// The `pnpm-workspace.yaml` preset to use for the new monorepo. If it's unset and `pnpm` is the chosen package manager, the default preset will be used.
-		#[arg(short, long, value_name = "PRESET_ID")]
+		#[arg(short, long, value_name = "PRESET_ID", add = preset_candidates(PresetKind::PnpmWorkspace))]
 		pnpm: Option<String>,
 
 		/// The id of the package preset to use for the root package. If unset, the default preset is used, along with the values set via cli flags.
-		#[arg(short, long, value_name = "PRESET_ID")]
+		#[arg(short, long, value_name = "PRESET_ID", add = preset_candidates(PresetKind::TsPackage))]
 		root_package: Option<String>,
 
 		#[command(flatten)]
@@ -45,7 +45,7 @@ pub enum TsCommands {
 		dir: Option<PathBuf>,
 
 		/// The package preset to use. If unset, the default preset is used, along with the values set via cli flags
-		#[arg(short, long, value_name = "ID")]
+		#[arg(short, long, value_name = "ID", add = preset_candidates(PresetKind::TsPackage))]
 		preset: Option<String>,
 
 		/// An optional list of tsconfig files where the new tsconfig file will be added as a reference.
@@ -57,7 +57,7 @@ pub enum TsCommands {
 		install: bool,
 
 		/// The vitest preset to use. It can be set to `default` to use the default preset.
-		#[arg(long, value_name = "ID")]
+		#[arg(long, value_name = "ID", add = preset_candidates(PresetKind::Vitest))]
 		vitest: Option<String>,
 
 		#[command(flatten)]
@@ -73,6 +73,7 @@ pub enum TsCommands {
 	/// Generates a `tsconfig.json` file from a preset.
 	Config {
 		/// The preset id
+		#[arg(add = preset_candidates(PresetKind::TsConfig))]
 		preset: String,
 
 		/// The output path of the generated file [default: `tsconfig.json`]
@@ -109,8 +110,10 @@ impl TsCommands {
 				dir: out_dir,
 				pnpm,
 			} => {
-				let mut root_package = if let Some(id) = root_package {
-					typescript.get_package_preset(&id)?
+				let root_package_id = root_package;
+
+				let mut root_package = if let Some(id) = &root_package_id {
+					typescript.get_package_preset(id)?
 				} else {
 					let mut package = TsPackagePreset::default();
 					package.oxlint = Some(OxlintPresetRef::Bool(true));
@@ -123,24 +126,38 @@ impl TsCommands {
 
 				let package_manager = *typescript.package_manager.get_or_insert_default();
 
-				let pnpm_config = if let Some(id) = pnpm {
-					Some(typescript.get_pnpm_preset(&id)?.config)
+				let pnpm_config = if let Some(id) = &pnpm {
+					Some(typescript.get_pnpm_preset(id)?.config)
 				} else if package_manager.is_pnpm() {
 					Some(PnpmWorkspace::default())
 				} else {
 					None
 				};
 
-				config
-					.create_ts_package(TsPackageSetup {
+				let mut manifest = GenerationManifest::new(
+					&out_dir,
+					GenerationCommand::TsMonorepo {
+						root_package: root_package_id,
+						pnpm,
+					},
+					cli_vars,
+				);
+
+				with_preset_policy(
+					root_package.overwrite,
+					config.create_ts_package(TsPackageSetup {
 						data: TsPackagePresetRef::Preset(root_package),
 						pkg_root: &out_dir,
 						tsconfig_files_to_update: vec![],
 						cli_vars,
 						package_type: PackageType::MonorepoRoot { pnpm: pnpm_config },
 						install,
-					})
-					.await?;
+						manifest: &mut manifest,
+					}),
+				)
+				.await?;
+
+				manifest.write()?;
 			}
 			Self::Package {
 				preset,
@@ -150,8 +167,8 @@ impl TsCommands {
 				vitest,
 				install,
 			} => {
-				let mut package = if let Some(id) = preset {
-					typescript.get_package_preset(&id)?
+				let mut package = if let Some(id) = &preset {
+					typescript.get_package_preset(id)?
 				} else {
 					TsPackagePreset::default()
 				};
@@ -160,8 +177,8 @@ impl TsCommands {
 					package.merge(overrides);
 				}
 
-				if let Some(vitest) = vitest {
-					package.vitest = Some(VitestPresetRef::PresetId(vitest))
+				if let Some(vitest) = &vitest {
+					package.vitest = Some(VitestPresetRef::PresetId(vitest.clone()))
 				}
 
 				let package_dir = dir.unwrap_or_else(|| {
@@ -172,16 +189,27 @@ impl TsCommands {
 						.into()
 				});
 
-				config
-					.create_ts_package(TsPackageSetup {
+				let mut manifest = GenerationManifest::new(
+					&package_dir,
+					GenerationCommand::TsPackage { preset, vitest },
+					cli_vars,
+				);
+
+				with_preset_policy(
+					package.overwrite,
+					config.create_ts_package(TsPackageSetup {
 						data: TsPackagePresetRef::Preset(package),
 						pkg_root: &package_dir,
 						tsconfig_files_to_update: update_tsconfig,
 						cli_vars,
 						package_type: PackageType::Normal,
 						install,
-					})
-					.await?;
+						manifest: &mut manifest,
+					}),
+				)
+				.await?;
+
+				manifest.write()?;
 			}
 		}
 
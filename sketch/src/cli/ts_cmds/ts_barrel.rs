@@ -93,8 +93,6 @@ impl TsBarrelArgs {
 
 		create_parent_dirs(&out_file)?;
 
-		let mut file = open_file_if_overwriting(overwrite, &out_file)?;
-
 		let template = read_to_string(concat!(
 			env!("CARGO_MANIFEST_DIR"),
 			"/templates/ts/barrel.ts.j2"
@@ -108,8 +106,7 @@ impl TsBarrelArgs {
 		let file_content =
 			Tera::one_off(&template, &context, false).context("Failed to create barrel file")?;
 
-		file.write_all(file_content.as_bytes())
-			.context("Failed to write barrel file")?;
+		write_file(&out_file, &file_content, overwrite)?;
 
 		Ok(())
 	}
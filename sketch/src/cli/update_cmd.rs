@@ -0,0 +1,244 @@
+use std::fmt::Write as _;
+
+use diffy::{ConflictStyle, MergeOptions, Patch};
+
+use super::*;
+
+/// The result of updating a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UpdateOutcome {
+	Created,
+	Updated,
+	Merged,
+	Unchanged,
+	Conflicts,
+	Rejected,
+	DeletedLocally,
+	/// The file was not written because of its overwrite policy.
+	Skipped,
+}
+
+impl Cli {
+	pub(super) async fn update_project(
+		dir: &Path,
+		config: Config,
+		cli_vars: IndexMap<String, Value>,
+		reject: bool,
+	) -> AppResult {
+		let previous = read_generation_manifest(dir)?;
+
+		let mut regenerated = Self::regenerate_project(dir, &previous, config, cli_vars).await?;
+
+		let mut skipped_files = Vec::new();
+
+		for entry in &regenerated.files {
+			let new_content = entry.content.as_deref().unwrap_or_default();
+
+			let outcome = update_file(
+				&dir.join(&entry.path),
+				previous.get_entry(&entry.path),
+				new_content,
+				reject,
+			)?;
+
+			let path = entry.path.display();
+
+			match outcome {
+				UpdateOutcome::Created => emit_message(format!("Created `{path}`")),
+				UpdateOutcome::Updated => emit_message(format!("Updated `{path}`")),
+				UpdateOutcome::Merged => emit_message(format!("Merged the changes into `{path}`")),
+				UpdateOutcome::Unchanged => {}
+				UpdateOutcome::Conflicts => emit_message(format!("Conflicts in `{path}`")),
+				UpdateOutcome::Rejected => {
+					emit_message(format!(
+						"Some changes could not be applied to `{path}`, see `{path}.rej`"
+					));
+				}
+				UpdateOutcome::DeletedLocally => {
+					emit_message(format!(
+						"Skipped `{path}` (it was deleted after being generated)"
+					));
+				}
+				// The skipped files are already reported by the overwrite policy
+				UpdateOutcome::Skipped => skipped_files.push(entry.path.clone()),
+			};
+		}
+
+		// The skipped files still contain the changes from the previous generation, so the previous content remains their base
+		for path in skipped_files {
+			regenerated.restore_entry(&path, previous.get_entry(&path));
+		}
+
+		for entry in &previous.files {
+			if regenerated.get_entry(&entry.path).is_none() {
+				emit_message(format!(
+					"`{}` is no longer generated by the presets and was left untouched",
+					entry.path.display()
+				));
+			}
+		}
+
+		regenerated.set_root(dir);
+		regenerated.write()
+	}
+}
+
+/// Writes a file according to its overwrite policy. Returns `false` if the file was not written.
+fn write_output(path: &Path, content: &[u8]) -> AppResult<bool> {
+	create_parent_dirs(path)?;
+
+	try_write_bytes(path, content, true)
+}
+
+/// Updates a file with its regenerated content, using the content from the previous generation (if there is one) as the common ancestor.
+fn update_file(
+	path: &Path,
+	previous_entry: Option<&ManifestEntry>,
+	new: &[u8],
+	reject: bool,
+) -> AppResult<UpdateOutcome> {
+	if !path.exists() {
+		if previous_entry.is_some() {
+			return Ok(UpdateOutcome::DeletedLocally);
+		}
+
+		write_output(path, new)?;
+
+		return Ok(UpdateOutcome::Created);
+	}
+
+	let current = read_bytes(path)?;
+	let base = previous_entry.and_then(|entry| entry.content.as_deref());
+
+	if current == new || base == Some(new) {
+		return Ok(UpdateOutcome::Unchanged);
+	}
+
+	if base == Some(current.as_slice()) {
+		if !write_output(path, new)? {
+			return Ok(UpdateOutcome::Skipped);
+		}
+
+		return Ok(UpdateOutcome::Updated);
+	}
+
+	let (Ok(base), Ok(current), Ok(new)) = (
+		str::from_utf8(base.unwrap_or_default()),
+		str::from_utf8(&current),
+		str::from_utf8(new),
+	) else {
+		// Binary files cannot be merged, so the local version is kept
+		return Ok(UpdateOutcome::Conflicts);
+	};
+
+	// Without a trailing newline, the conflict markers would end up on the same line as the content
+	let (base, current, new) = (
+		with_trailing_newline(base),
+		with_trailing_newline(current),
+		with_trailing_newline(new),
+	);
+
+	if reject {
+		let (merged, rejected_hunks) = apply_hunks(&base, &current, &new);
+
+		if !write_output(path, merged.as_bytes())? {
+			return Ok(UpdateOutcome::Skipped);
+		}
+
+		if rejected_hunks.is_empty() {
+			return Ok(UpdateOutcome::Merged);
+		}
+
+		let mut rej_content = String::new();
+
+		let file_name = path.display();
+		writeln!(rej_content, "--- {file_name}").unwrap();
+		writeln!(rej_content, "+++ {file_name}").unwrap();
+
+		for hunk in rejected_hunks {
+			rej_content.push_str(&hunk);
+		}
+
+		let mut rej_path = path.as_os_str().to_owned();
+		rej_path.push(".rej");
+
+		write_output(Path::new(&rej_path), rej_content.as_bytes())?;
+
+		Ok(UpdateOutcome::Rejected)
+	} else {
+		match MergeOptions::new()
+			.set_conflict_style(ConflictStyle::Merge)
+			.merge(&base, &current, &new)
+		{
+			Ok(merged) => {
+				if !write_output(path, merged.as_bytes())? {
+					return Ok(UpdateOutcome::Skipped);
+				}
+
+				Ok(UpdateOutcome::Merged)
+			}
+			Err(with_conflicts) => {
+				if !write_output(path, label_conflicts(&with_conflicts).as_bytes())? {
+					return Ok(UpdateOutcome::Skipped);
+				}
+
+				Ok(UpdateOutcome::Conflicts)
+			}
+		}
+	}
+}
+
+fn with_trailing_newline(content: &str) -> String {
+	if content.is_empty() || content.ends_with('\n') {
+		content.to_string()
+	} else {
+		format!("{content}\n")
+	}
+}
+
+// Uses clearer labels for the two sides of the conflicts
+fn label_conflicts(content: &str) -> String {
+	content
+		.split_inclusive('\n')
+		.map(|line| match line.trim_end_matches('\n') {
+			"<<<<<<< ours" => line.replacen("ours", "current", 1),
+			">>>>>>> theirs" => line.replacen("theirs", "generated", 1),
+			_ => line.to_string(),
+		})
+		.collect()
+}
+
+/// Applies the changes between the base and the new content to the current content, one hunk at a time.
+///
+/// Returns the result, along with the hunks that could not be applied.
+fn apply_hunks(base: &str, current: &str, new: &str) -> (String, Vec<String>) {
+	let patch = diffy::create_patch(base, new).to_string();
+
+	let mut hunks: Vec<String> = Vec::new();
+
+	for line in patch.split_inclusive('\n') {
+		if line.starts_with("@@ ") {
+			hunks.push(String::new());
+		}
+
+		if let Some(hunk) = hunks.last_mut() {
+			hunk.push_str(line);
+		}
+	}
+
+	let mut result = current.to_string();
+	let mut rejected = Vec::new();
+
+	for hunk in hunks {
+		let applied = Patch::from_str(&format!("--- original\n+++ modified\n{hunk}"))
+			.ok()
+			.and_then(|patch| diffy::apply(&result, &patch).ok());
+
+		match applied {
+			Some(updated) => result = updated,
+			None => rejected.push(hunk),
+		}
+	}
+
+	(result, rejected)
+}
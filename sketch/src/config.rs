@@ -12,6 +12,23 @@ use crate::{
 mod config_setup;
 use config_setup::extract_config_from_file;
 
+mod env_interpolation;
+use env_interpolation::interpolate_env_vars;
+mod extends;
+pub use extends::*;
+mod preset_diff;
+pub use preset_diff::*;
+mod preset_explain;
+pub use preset_explain::*;
+mod presets;
+pub(crate) use presets::preset_map_path;
+mod sources;
+pub use sources::ConfigSources;
+mod validation;
+mod versioning;
+use versioning::check_version_on_error;
+pub use versioning::{CONFIG_VERSION, MigrationReport, migrate_config_file};
+
 impl Config {
 	pub fn new() -> Self {
 		Self {
@@ -28,6 +45,55 @@ impl Config {
 		Ok(())
 	}
 
+	/// The config files where each preset, template and variable has been defined.
+	pub const fn sources(&self) -> &ConfigSources {
+		&self.sources
+	}
+
+	/// Returns a copy of this config that can be serialized and loaded again as a standalone config file, with the extended configs already merged in and the vars set via cli added to the global vars.
+	#[must_use]
+	pub fn to_dump(&self, cli_vars: &IndexMap<String, Value>) -> Self {
+		let mut dump = self.clone();
+
+		dump.version = Some(CONFIG_VERSION.to_string());
+		// The extended configs have already been merged
+		dump.extends.clear();
+		dump.root = false;
+		dump.vars.extend(
+			cli_vars
+				.iter()
+				.map(|(k, v)| (k.clone(), v.clone())),
+		);
+
+		dump
+	}
+
+	/// Merges the profile with the given name on top of the rest of the config.
+	pub fn apply_profile(&mut self, name: &str) -> AppResult {
+		let profile = self.profiles.get(name).cloned().ok_or_else(|| {
+			let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+
+			anyhow!(
+				"Profile `{name}` not found. The available profiles are: [ {} ]",
+				available.join(", ")
+			)
+		})?;
+
+		if !profile.extends.is_empty() || !profile.profiles.is_empty() {
+			return Err(anyhow!("Profile `{name}` cannot contain `extends` or `profiles`").into());
+		}
+
+		self.merge(profile);
+		self.active_profile = Some(name.to_string());
+
+		Ok(())
+	}
+
+	/// The name of the profile that has been applied to this config, if there is one.
+	pub fn active_profile(&self) -> Option<&str> {
+		self.active_profile.as_deref()
+	}
+
 	pub(crate) const fn can_overwrite(&self) -> bool {
 		!self.no_overwrite
 	}
@@ -44,6 +110,17 @@ pub struct Config {
 	#[merge(with = overwrite_if_none)]
 	pub(crate) config_file: Option<PathBuf>,
 
+	#[serde(skip)]
+	pub(crate) sources: ConfigSources,
+
+	#[serde(skip)]
+	#[merge(skip)]
+	pub(crate) active_profile: Option<String>,
+
+	/// The version of the config format. Configs that use an older format can be updated with `sketch config migrate`.
+	#[merge(skip)]
+	pub version: Option<String>,
+
 	/// The configuration for typescript projects.
 	#[merge(with = merge_options)]
 	pub typescript: Option<TypescriptConfig>,
@@ -58,13 +135,28 @@ pub struct Config {
 	/// The path to the templates directory.
 	pub templates_dir: Option<PathBuf>,
 
-	/// Do not overwrite existing files.
+	/// Do not overwrite existing files. It takes precedence over the overwrite policies.
 	#[merge(with = overwrite_if_true)]
 	pub no_overwrite: bool,
 
+	/// The policy for the files that already exist [default: `overwrite`]. It can be overridden by the presets and by `overwrite_rules`.
+	pub overwrite: Option<OverwritePolicy>,
+
+	/// A map of glob patterns to overwrite policies. The patterns are matched against the end of the output path (so that `LICENSE` matches any file named `LICENSE`). When several patterns match a file, the last one is used.
+	pub overwrite_rules: IndexMap<String, OverwritePolicy>,
+
+	/// Never ask for the values of missing template variables, and exit with an error instead. Variables are only requested when the input is interactive.
+	#[merge(with = overwrite_if_true)]
+	pub no_input: bool,
+
+	/// Stops the search for config files in the parent directories when this config is found.
+	/// The XDG config, if present, is still used as the lowest priority config.
+	#[merge(skip)]
+	pub root: bool,
+
 	#[merge(skip)]
-	/// The paths (absolute, or relative to the originating config file) to the config files to extend.
-	pub extends: IndexSet<PathBuf>,
+	/// The config files to extend. Each element can be a path (absolute, or relative to the originating config file) or a config file inside a git repository.
+	pub extends: IndexSet<ConfigFileRef>,
 
 	/// A map that contains template definitions.
 	pub templates: IndexMap<String, String>,
@@ -87,9 +179,44 @@ pub struct Config {
 	/// Configurations and presets relating to Github
 	pub github: GithubConfig,
 
+	/// A map of named profiles. Each profile is a partial config that is merged on top of the rest of the config when it is selected with `--profile` or the `SKETCH_PROFILE` environment variable.
+	#[merge(with = merge_profiles)]
+	pub profiles: IndexMap<String, Self>,
+
 	/// The global variables that will be available for every template being generated.
 	/// They are overridden by vars set in a template's local context or via the cli.
 	pub vars: IndexMap<String, Value>,
+
+	/// The declarations for the global variables. They are checked (along with the declarations of each templating preset) before rendering any template.
+	pub var_declarations: IndexMap<String, VarDeclaration>,
+}
+
+/// Defines what happens when a generated file already exists.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default, ValueEnum)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum OverwritePolicy {
+	/// Replaces the existing file.
+	#[default]
+	Overwrite,
+	/// Keeps the existing file.
+	Skip,
+	/// Exits with an error.
+	Error,
+	/// Copies the existing file to `<file>.orig` before replacing it.
+	Backup,
+	/// Shows the changes and asks whether the existing file should be replaced.
+	Ask,
+}
+
+fn merge_profiles(left: &mut IndexMap<String, Config>, right: IndexMap<String, Config>) {
+	for (name, profile) in right {
+		if let Some(existing) = left.get_mut(&name) {
+			existing.merge(profile);
+		} else {
+			left.insert(name, profile);
+		}
+	}
 }
 
 impl Config {
@@ -104,21 +231,20 @@ impl Config {
 
 		let paths_to_extend = take(&mut self.extends);
 
-		for target_rel_path in &paths_to_extend {
-			let target_abs_path = get_abs_path(&config_file_parent_dir.join(target_rel_path))?;
+		for target in &paths_to_extend {
+			let target_abs_path = target.resolve(&config_file_parent_dir)?;
 
 			let was_absent = processed_sources.insert(target_abs_path.clone());
 
 			if !was_absent {
-				let chain: Vec<_> = processed_sources
+				let chain: Vec<String> = processed_sources
 					.iter()
-					.map(|source| source.to_string_lossy())
+					.chain([&target_abs_path])
+					.map(|source| source.to_string_lossy().to_string())
 					.collect();
 
-				return Err(AppError::CircularDependency(format!(
-					"Found circular dependency to the config file {}. The full processed path is: {}",
-					target_abs_path.display(),
-					chain.join(" -> ")
+				return Err(AppError::CircularDependency(DependencyCycle::new(
+					None, chain,
 				)));
 			}
 
@@ -152,8 +278,11 @@ impl Config {
 		// Should not show up in the `extends` list we insert below
 		processed_sources.swap_remove(&config_file);
 
-		// Replace rel paths with abs paths for better debugging
-		self.extends = processed_sources;
+		// Replace rel paths and git sources with abs paths for better debugging
+		self.extends = processed_sources
+			.into_iter()
+			.map(ConfigFileRef::from)
+			.collect();
 
 		Ok(self)
 	}
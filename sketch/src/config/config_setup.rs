@@ -1,28 +1,28 @@
 use super::*;
 
 pub(crate) fn extract_config_from_file(config_file_abs_path: &Path) -> Result<Config, AppError> {
-	let extension = get_extension(config_file_abs_path)?;
-
-	let mut config: Config = if extension == "yaml" || extension == "yml" {
-		deserialize_yaml(config_file_abs_path)?
-	} else if extension == "toml" {
-		deserialize_toml(config_file_abs_path)?
-	} else if extension == "json" {
-		deserialize_json(config_file_abs_path)?
-	} else {
-		return Err(AppError::DeserializationError {
-			file: config_file_abs_path.to_path_buf(),
-			error: format!(
-				"Invalid config format for `{}`. Allowed formats are: yaml, toml, json",
-				config_file_abs_path.display()
-			),
-		});
-	};
+	let mut config = deserialize_config(config_file_abs_path)?;
 
 	config.config_file = Some(config_file_abs_path.to_path_buf());
 
 	let config_parent_dir = get_parent_dir(config_file_abs_path)?;
 
+	for profile in config.profiles.values_mut() {
+		setup_file_paths(profile, config_file_abs_path, config_parent_dir)?;
+	}
+
+	setup_file_paths(&mut config, config_file_abs_path, config_parent_dir)?;
+
+	Ok(config)
+}
+
+fn setup_file_paths(
+	config: &mut Config,
+	config_file_abs_path: &Path,
+	config_parent_dir: &Path,
+) -> AppResult {
+	config.sources = ConfigSources::from_file(config, config_file_abs_path);
+
 	if let Some(templates_dir) = &config.templates_dir {
 		let templates_dir = config_parent_dir.join(templates_dir);
 
@@ -32,7 +32,72 @@ pub(crate) fn extract_config_from_file(config_file_abs_path: &Path) -> Result<Co
 		config.templates_dir = Some(get_abs_path(&templates_dir)?);
 	}
 
-	Ok(config)
+	Ok(())
+}
+
+fn deserialize_config(path: &Path) -> AppResult<Config> {
+	let format = Format::from_path(path).map_err(|_| AppError::DeserializationError {
+		file: path.to_path_buf(),
+		error: format!(
+			"Invalid config format for `{}`. Allowed formats are: yaml, toml, json",
+			path.display()
+		),
+		location: None,
+	})?;
+
+	let contents = read_file_to_string(path)?;
+
+	let mut raw_config: Option<Value> = None;
+
+	// The typed deserialization provides more precise errors, so the untyped value
+	// is only used when there is something to interpolate
+	let result = if contents.contains("${env:") {
+		let mut raw = format.deserialize::<Value>(path, &contents)?;
+
+		interpolate_env_vars(&mut raw, path, &contents)?;
+
+		let result = Config::deserialize(&raw).map_err(|e| {
+			let error = e.to_string();
+
+			// The interpolated values do not have a position in the file, so the error is located
+			// by deserializing the original contents, which fail in the same way unless the error
+			// comes from an interpolated value
+			let location = match format.deserialize::<Config>(path, &contents) {
+				Err(AppError::DeserializationError {
+					error: original_error,
+					location,
+					..
+				}) if original_error.ends_with(&error) => location,
+				_ => None,
+			};
+
+			AppError::DeserializationError {
+				file: path.to_path_buf(),
+				error,
+				location,
+			}
+		});
+
+		raw_config = Some(raw);
+
+		result
+	} else {
+		format.deserialize(path, &contents)
+	};
+
+	match result {
+		Ok(config) => {
+			config.check_version(path);
+			Ok(config)
+		}
+		Err(error @ AppError::DeserializationError { .. }) => {
+			let raw_config =
+				raw_config.or_else(|| format.deserialize::<Value>(path, &contents).ok());
+
+			Err(check_version_on_error(raw_config.as_ref(), path, error))
+		}
+		Err(error) => Err(error),
+	}
 }
 
 impl Config {
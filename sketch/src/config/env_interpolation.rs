@@ -0,0 +1,137 @@
+use super::*;
+
+const ENV_PREFIX: &str = "${env:";
+
+/// Replaces `${env:NAME}` and `${env:NAME:-default}` in the string values of a parsed config file.
+///
+/// A literal `${env:` sequence can be written as `$${env:`.
+///
+/// The original contents of the file are used to point the errors at the position of the expressions in the file.
+pub(crate) fn interpolate_env_vars(value: &mut Value, file: &Path, contents: &str) -> AppResult {
+	Interpolator {
+		file,
+		contents,
+		occurrences: HashMap::new(),
+	}
+	.interpolate_value(value, &mut Vec::new())
+}
+
+struct Interpolator<'a> {
+	file: &'a Path,
+	contents: &'a str,
+	/// How many times each expression has been found so far, to locate its occurrences in the file.
+	occurrences: HashMap<String, usize>,
+}
+
+impl Interpolator<'_> {
+	fn interpolate_value(&mut self, value: &mut Value, key_path: &mut Vec<String>) -> AppResult {
+		match value {
+			Value::String(string) if string.contains('$') => {
+				*string = self.interpolate_string(string, key_path)?;
+			}
+			Value::Array(items) => {
+				for (i, item) in items.iter_mut().enumerate() {
+					key_path.push(format!("[{i}]"));
+					self.interpolate_value(item, key_path)?;
+					key_path.pop();
+				}
+			}
+			Value::Object(map) => {
+				for (key, item) in map.iter_mut() {
+					key_path.push(key.clone());
+					self.interpolate_value(item, key_path)?;
+					key_path.pop();
+				}
+			}
+			_ => {}
+		};
+
+		Ok(())
+	}
+
+	fn interpolate_string(&mut self, input: &str, key_path: &[String]) -> AppResult<String> {
+		let mut output = String::with_capacity(input.len());
+		let mut rest = input;
+
+		while let Some(idx) = rest.find('$') {
+			output.push_str(&rest[..idx]);
+			rest = &rest[idx..];
+
+			if let Some(escaped) = rest.strip_prefix("$${env:") {
+				output.push_str(ENV_PREFIX);
+				rest = escaped;
+			} else if let Some(expr) = rest.strip_prefix(ENV_PREFIX)
+				&& let Some(end) = expr.find('}')
+			{
+				let expression = &rest[..ENV_PREFIX.len() + end + 1];
+				let occurrence = self
+					.occurrences
+					.entry(expression.to_string())
+					.or_default();
+				*occurrence += 1;
+
+				let (name, default) = match expr[..end].split_once(":-") {
+					Some((name, default)) => (name, Some(default)),
+					None => (&expr[..end], None),
+				};
+
+				// Like in the shell, the default is also used when the variable is set but empty
+				let value = env::var(name)
+					.ok()
+					.filter(|value| default.is_none() || !value.is_empty());
+
+				match (value, default) {
+					(Some(value), _) => output.push_str(&value),
+					(None, Some(default)) => output.push_str(default),
+					(None, None) => {
+						return Err(AppError::MissingEnvVar {
+							name: name.to_string(),
+							key_path: format_key_path(key_path),
+							file: self.file.to_path_buf(),
+							location: self.locate(expression),
+						});
+					}
+				};
+
+				rest = &expr[end + 1..];
+			} else {
+				output.push('$');
+				rest = &rest[1..];
+			}
+		}
+
+		output.push_str(rest);
+
+		Ok(output)
+	}
+
+	/// Finds the position of the last occurrence of an expression that has been found so far, in the original contents of the file.
+	///
+	/// The values are visited in the same order as they appear in the file, so the n-th visited occurrence is the n-th one in the file.
+	fn locate(&self, expression: &str) -> Option<SourceLocation> {
+		let occurrence = *self.occurrences.get(expression)?;
+
+		let (offset, _) = self
+			.contents
+			.match_indices(expression)
+			// Escaped expressions are not interpolated
+			.filter(|(offset, _)| !self.contents[..*offset].ends_with('$'))
+			.nth(occurrence - 1)?;
+
+		SourceLocation::from_offset(self.contents, offset)
+	}
+}
+
+fn format_key_path(key_path: &[String]) -> String {
+	let mut output = String::new();
+
+	for segment in key_path {
+		if !output.is_empty() && !segment.starts_with('[') {
+			output.push('.');
+		}
+
+		output.push_str(segment);
+	}
+
+	output
+}
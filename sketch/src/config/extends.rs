@@ -0,0 +1,130 @@
+use std::fs::rename;
+
+use super::*;
+use crate::git::{clone_repo, is_commit_hash, needs_refresh, refresh_repo};
+
+/// A reference to a config file to extend.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum ConfigFileRef {
+	/// The path (absolute, or relative to the originating config file) to a local config file.
+	Path(PathBuf),
+
+	/// A config file inside a git repository.
+	Git(GitConfigFile),
+}
+
+impl From<PathBuf> for ConfigFileRef {
+	fn from(value: PathBuf) -> Self {
+		Self::Path(value)
+	}
+}
+
+/// A config file inside a git repository.
+///
+/// The repository is cloned in the `sketch` directory inside `XDG_CACHE_HOME` (or `$HOME/.cache`), and the clone is reused in later runs. Unless `rev` is a commit hash, the clone is updated with the latest commits of the revision once a day, when the repository is reachable. Otherwise, the cached clone is used as it is.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct GitConfigFile {
+	/// The url of the repository.
+	pub repo: String,
+	/// The revision (branch, tag or commit) to check out. If unset, the default branch is used.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rev: Option<String>,
+	/// The path to the config file, relative to the root of the repository.
+	pub path: PathBuf,
+}
+
+impl ConfigFileRef {
+	/// Returns the absolute path to the config file, cloning its repository if necessary.
+	pub(crate) fn resolve(&self, parent_dir: &Path) -> AppResult<PathBuf> {
+		match self {
+			Self::Path(path) => get_abs_path(&parent_dir.join(path)),
+			Self::Git(git_file) => {
+				let repo_dir = git_file.get_or_clone_repo()?;
+
+				get_abs_path(&repo_dir.join(&git_file.path))
+			}
+		}
+	}
+}
+
+impl GitConfigFile {
+	fn cache_dir(&self) -> AppResult<PathBuf> {
+		let cache_home = if let Ok(env_val) = env::var("XDG_CACHE_HOME") {
+			PathBuf::from(env_val)
+		} else {
+			env::home_dir()
+				.context("Could not find the home directory")?
+				.join(".cache")
+		};
+
+		// The name of the repo is kept for readability, while the hash makes the directory unique for each repo and revision
+		let repo_name: String = self
+			.repo
+			.trim_end_matches('/')
+			.trim_end_matches(".git")
+			.rsplit(['/', ':'])
+			.next()
+			.unwrap_or_default()
+			.chars()
+			.filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+			.collect();
+
+		let hash = hash_content(
+			format!("{}\n{}", self.repo, self.rev.as_deref().unwrap_or_default()).as_bytes(),
+		);
+
+		let dir_name = format!("{repo_name}-{}", &hash[..16]);
+
+		Ok(cache_home.join("sketch/repos").join(dir_name))
+	}
+
+	fn get_or_clone_repo(&self) -> AppResult<PathBuf> {
+		let repo_dir = self.cache_dir()?;
+		let repo = &self.repo;
+		let rev = self.rev.as_deref();
+
+		if repo_dir.is_dir() {
+			// Branches (and the default branch) can point to new commits, so they are updated once in a while, when possible
+			if !rev.is_some_and(is_commit_hash)
+				&& needs_refresh(&repo_dir)
+				&& let Err(e) = refresh_repo(repo, rev, &repo_dir)
+			{
+				emit(OutputEvent::Warning {
+					message: format!(
+						"Using the cached clone of `{repo}`, which could not be updated: {e}"
+					),
+				});
+			}
+
+			return Ok(repo_dir);
+		}
+
+		// Cloning in a separate directory so that an interrupted clone is never reused
+		let mut tmp_dir = repo_dir.clone().into_os_string();
+		tmp_dir.push(".tmp");
+		let tmp_dir = PathBuf::from(tmp_dir);
+
+		if tmp_dir.exists() {
+			remove_dir_all(&tmp_dir).with_context(|| {
+				format!("Could not empty the directory `{}`", tmp_dir.display())
+			})?;
+		}
+
+		create_parent_dirs(&tmp_dir)?;
+
+		clone_repo(repo, rev, &tmp_dir)?;
+
+		rename(&tmp_dir, &repo_dir).with_context(|| {
+			format!(
+				"Could not move the clone of `{repo}` to `{}`",
+				repo_dir.display()
+			)
+		})?;
+
+		Ok(repo_dir)
+	}
+}
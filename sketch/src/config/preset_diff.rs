@@ -0,0 +1,130 @@
+use super::*;
+
+/// A difference between two values, at a given key path (like `package.edition` or `steps[0].run`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ValueChange {
+	/// The value is only present in the second preset.
+	Added { path: String, value: Value },
+	/// The value is only present in the first preset.
+	Removed { path: String, value: Value },
+	/// The value is present in both presets, but it is different.
+	Changed {
+		path: String,
+		old: Value,
+		new: Value,
+	},
+}
+
+impl Display for ValueChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Added { path, value } => write!(f, "+ {}: {value}", display_key_path(path)),
+			Self::Removed { path, value } => write!(f, "- {}: {value}", display_key_path(path)),
+			Self::Changed { path, old, new } => {
+				write!(f, "~ {}: {old} -> {new}", display_key_path(path))
+			}
+		}
+	}
+}
+
+impl Config {
+	/// Resolves two presets of the same kind and returns the differences between them, by key path.
+	pub fn diff_presets(&self, kind: PresetKind, a: &str, b: &str) -> AppResult<Vec<ValueChange>> {
+		let a = self.resolve_preset(kind, a)?;
+		let b = self.resolve_preset(kind, b)?;
+
+		Ok(diff_values(&a, &b))
+	}
+}
+
+/// Returns the differences between two values.
+///
+/// Maps are compared by key and arrays by index, so that each change refers to the innermost value that is different.
+pub fn diff_values(old: &Value, new: &Value) -> Vec<ValueChange> {
+	let mut changes = Vec::new();
+
+	diff_values_at("", old, new, &mut changes);
+
+	changes
+}
+
+fn diff_values_at(path: &str, old: &Value, new: &Value, changes: &mut Vec<ValueChange>) {
+	match (old, new) {
+		(Value::Object(old_map), Value::Object(new_map)) => {
+			for (key, old_value) in old_map {
+				let key_path = join_key_path(path, key);
+
+				match new_map.get(key) {
+					Some(new_value) => diff_values_at(&key_path, old_value, new_value, changes),
+					None => changes.push(ValueChange::Removed {
+						path: key_path,
+						value: old_value.clone(),
+					}),
+				}
+			}
+
+			for (key, new_value) in new_map {
+				if !old_map.contains_key(key) {
+					changes.push(ValueChange::Added {
+						path: join_key_path(path, key),
+						value: new_value.clone(),
+					});
+				}
+			}
+		}
+		(Value::Array(old_items), Value::Array(new_items)) => {
+			for (i, old_value) in old_items.iter().enumerate() {
+				let index_path = format!("{path}[{i}]");
+
+				match new_items.get(i) {
+					Some(new_value) => diff_values_at(&index_path, old_value, new_value, changes),
+					None => changes.push(ValueChange::Removed {
+						path: index_path,
+						value: old_value.clone(),
+					}),
+				}
+			}
+
+			for (i, new_value) in new_items.iter().enumerate().skip(old_items.len()) {
+				changes.push(ValueChange::Added {
+					path: format!("{path}[{i}]"),
+					value: new_value.clone(),
+				});
+			}
+		}
+		_ => {
+			if old != new {
+				changes.push(ValueChange::Changed {
+					path: path.to_string(),
+					old: old.clone(),
+					new: new.clone(),
+				});
+			}
+		}
+	}
+}
+
+/// Adds a key to a key path. Keys that contain special characters (like the `./index` key in the `exports` of a `package.json` file) are quoted.
+pub(crate) fn join_key_path(path: &str, key: &str) -> String {
+	let is_plain = !key.is_empty()
+		&& key
+			.chars()
+			.all(|c| !matches!(c, '.' | '[' | ']' | '"') && !c.is_whitespace());
+
+	let key = if is_plain {
+		key.to_string()
+	} else {
+		format!("{key:?}")
+	};
+
+	if path.is_empty() {
+		key
+	} else {
+		format!("{path}.{key}")
+	}
+}
+
+pub(crate) const fn display_key_path(path: &str) -> &str {
+	if path.is_empty() { "." } else { path }
+}
@@ -0,0 +1,268 @@
+use super::{
+	presets::{get_extensible_preset, preset_content, preset_map_path, with_extensible_presets},
+	*,
+};
+use crate::merging_strategies::{MergeObserver, merge_presets_observed};
+
+/// A resolved preset, where each value is annotated with the id of the preset that set it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetExplanation {
+	/// The ids of the presets in the order in which they are merged, from the lowest to the highest priority. The last one is the explained preset.
+	pub extension_order: Vec<String>,
+	/// The values of the resolved preset, by key path.
+	pub fields: Vec<FieldOrigin>,
+}
+
+/// A value in a resolved preset, along with the preset that set it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldOrigin {
+	/// The key path of the value (like `package.edition`). Lists are not split, since their items can come from different presets.
+	pub path: String,
+	pub value: Value,
+	/// The id of the preset that last set this value, or `default` if none of the presets set it and the value is the default one for its field.
+	pub preset_id: String,
+}
+
+impl Display for PresetExplanation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Extension order: {}", self.extension_order.join(" -> "))?;
+		writeln!(f)?;
+
+		let lines: Vec<(String, &str)> = self
+			.fields
+			.iter()
+			.map(|field| {
+				(
+					format!("{}: {}", display_key_path(&field.path), field.value),
+					field.preset_id.as_str(),
+				)
+			})
+			.collect();
+
+		// Long values are not taken into account for the alignment of the ids
+		let width = lines
+			.iter()
+			.map(|(line, _)| line.chars().count())
+			.filter(|len| *len <= 60)
+			.max()
+			.unwrap_or(0);
+
+		for (line, id) in lines {
+			writeln!(f, "{line:<width$}  # {id}")?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Config {
+	/// Resolves a preset that can extend other presets, and returns the order in which the presets are merged, along with the preset that set each value.
+	pub fn explain_preset(&self, kind: PresetKind, id: &str) -> AppResult<PresetExplanation> {
+		with_extensible_presets!(self, kind, |store| explain_extensible_preset(
+			id,
+			store,
+			|preset_id| self.defined_paths(kind, preset_id)
+		))
+	}
+
+	/// Returns the key paths of the values that are written in the definition of a preset, in its config file.
+	/// Returns `None` if the definition cannot be read, like for the presets that do not come from a config file.
+	fn defined_paths(&self, kind: PresetKind, id: &str) -> Option<Vec<String>> {
+		let file = self.sources.preset_source(kind, id)?;
+		let contents = read_file_to_string(file).ok()?;
+		let raw: Value = Format::from_path(file)
+			.ok()?
+			.deserialize(file, &contents)
+			.ok()?;
+
+		let definition = preset_map_path(kind)
+			.iter()
+			.copied()
+			.chain([id])
+			.try_fold(&raw, |value, key| value.get(key))?;
+
+		Some(leaf_values(definition).into_keys().collect())
+	}
+}
+
+fn explain_extensible_preset<T: ExtensiblePreset + Serialize + Default>(
+	id: &str,
+	store: &IndexMap<String, T>,
+	defined_paths: impl Fn(&str) -> Option<Vec<String>>,
+) -> AppResult<PresetExplanation> {
+	let mut tracker = OriginTracker::default();
+
+	let preset = merge_presets_observed(
+		get_extensible_preset(id, store)?,
+		id,
+		store,
+		&mut IndexSet::new(),
+		&mut tracker,
+	)?;
+
+	let origins = tracker
+		.origins
+		.shift_remove(id)
+		.unwrap_or_default();
+	let extension_order = tracker
+		.extension_orders
+		.shift_remove(id)
+		.unwrap_or_else(|| vec![id.to_string()]);
+
+	// The paths written in the definition of each preset. If one of them cannot be read,
+	// the origins are taken from the merged values alone and no value is labelled as a default
+	let defined_paths: Option<Vec<(&String, Vec<String>)>> = extension_order
+		.iter()
+		.map(|preset_id| Some((preset_id, defined_paths(preset_id)?)))
+		.collect();
+
+	let default_values = leaf_values(&preset_content(id, &T::default())?);
+
+	let fields = leaf_values(&preset_content(id, &preset)?)
+		.into_iter()
+		.map(|(path, value)| {
+			let tracked_origin = || {
+				origins
+					.get(&path)
+					.cloned()
+					.unwrap_or_else(|| id.to_string())
+			};
+
+			let preset_id = match &defined_paths {
+				Some(defined_paths) => {
+					// The last preset that writes this value is the one that set it
+					let setter = defined_paths.iter().rev().find(|(_, paths)| {
+						paths
+							.iter()
+							.any(|defined| key_paths_overlap(defined, &path))
+					});
+
+					match setter {
+						Some((preset_id, _)) => (*preset_id).clone(),
+						// The values that were not set by any preset come from the defaults of the fields
+						None if default_values.get(&path) == Some(&value) => "default".to_string(),
+						None => tracked_origin(),
+					}
+				}
+				None => tracked_origin(),
+			};
+
+			FieldOrigin {
+				path,
+				value,
+				preset_id,
+			}
+		})
+		.collect();
+
+	Ok(PresetExplanation {
+		extension_order,
+		fields,
+	})
+}
+
+/// Checks if a key path is the same as another one, or if one of them contains the other.
+fn key_paths_overlap(first: &str, second: &str) -> bool {
+	let contains = |outer: &str, inner: &str| {
+		outer.is_empty()
+			|| inner
+				.strip_prefix(outer)
+				.is_some_and(|rest| rest.starts_with('.'))
+	};
+
+	first == second || contains(first, second) || contains(second, first)
+}
+
+/// Keeps track of the preset that set each value, while the presets are merged.
+#[derive(Default)]
+struct OriginTracker {
+	/// The origin of each value of the presets that have been resolved so far, by preset id and key path.
+	origins: IndexMap<String, IndexMap<String, String>>,
+	/// The extension order of the presets that have been resolved so far.
+	extension_orders: IndexMap<String, Vec<String>>,
+	base_values: IndexMap<String, Value>,
+	overlay_values: IndexMap<String, Value>,
+}
+
+impl<T: Serialize> MergeObserver<T> for OriginTracker {
+	fn before_merge(&mut self, id: &str, extended_id: &str, base: &T, overlay: &T) -> AppResult {
+		self.base_values = leaf_values(&preset_content(extended_id, base)?);
+		self.overlay_values = leaf_values(&preset_content(id, overlay)?);
+
+		// The presets that do not extend anything set all of their values
+		for (preset_id, values) in [(extended_id, &self.base_values), (id, &self.overlay_values)] {
+			self.origins
+				.entry(preset_id.to_string())
+				.or_insert_with(|| {
+					values
+						.keys()
+						.map(|path| (path.clone(), preset_id.to_string()))
+						.collect()
+				});
+			self.extension_orders
+				.entry(preset_id.to_string())
+				.or_insert_with(|| vec![preset_id.to_string()]);
+		}
+
+		Ok(())
+	}
+
+	fn after_merge(&mut self, id: &str, extended_id: &str, merged: &T) -> AppResult {
+		let overlay_origins = &self.origins[id];
+		let base_origins = &self.origins[extended_id];
+
+		// When a value is the same on both sides, it is attributed to the preset that is merged last, which is the one that set it
+		let origins = leaf_values(&preset_content(id, merged)?)
+			.into_iter()
+			.map(|(path, value)| {
+				let origin = if self.overlay_values.get(&path) == Some(&value) {
+					overlay_origins.get(&path)
+				} else if self.base_values.get(&path) == Some(&value) {
+					base_origins.get(&path)
+				} else {
+					// The value is a combination of both sides (like a merged list)
+					overlay_origins
+						.get(&path)
+						.or_else(|| base_origins.get(&path))
+				};
+
+				let origin = origin.cloned().unwrap_or_else(|| id.to_string());
+
+				(path, origin)
+			})
+			.collect();
+
+		self.origins.insert(id.to_string(), origins);
+
+		let base_order = self.extension_orders[extended_id].clone();
+
+		self.extension_orders
+			.entry(id.to_string())
+			.or_default()
+			.splice(0..0, base_order);
+
+		Ok(())
+	}
+}
+
+/// Returns the innermost values of a value, by key path. Lists and empty maps are not split.
+fn leaf_values(value: &Value) -> IndexMap<String, Value> {
+	fn collect(path: &str, value: &Value, leaves: &mut IndexMap<String, Value>) {
+		match value {
+			Value::Object(map) if !map.is_empty() => {
+				for (key, value) in map {
+					collect(&join_key_path(path, key), value, leaves);
+				}
+			}
+			_ => {
+				leaves.insert(path.to_string(), value.clone());
+			}
+		}
+	}
+
+	let mut leaves = IndexMap::new();
+
+	collect("", value, &mut leaves);
+
+	leaves
+}
@@ -0,0 +1,252 @@
+use super::*;
+
+/// Evaluates an expression with the map that contains the presets of a given kind, or `None` if the section of the config that contains it is not set.
+///
+/// The second expression, if present, is used for the kinds of presets that cannot extend other presets.
+macro_rules! with_preset_store {
+	($config:expr, $kind:expr, |$store:ident| $body:expr) => {
+		with_preset_store!($config, $kind, |$store| $body, |$store| $body)
+	};
+
+	($config:expr, $kind:expr, |$store:ident| $body:expr, |$other_store:ident| $other_body:expr) => {{
+		let config = $config;
+		let typescript = config.typescript.as_ref();
+		let docker = config.docker.as_ref();
+
+		match $kind {
+			PresetKind::PackageJson => {
+				let $store = typescript.map(|ts| &ts.package_json_presets);
+				$body
+			}
+			PresetKind::TsConfig => {
+				let $store = typescript.map(|ts| &ts.ts_config_presets);
+				$body
+			}
+			PresetKind::Oxlint => {
+				let $store = typescript.map(|ts| &ts.oxlint_presets);
+				$body
+			}
+			PresetKind::PnpmWorkspace => {
+				let $store = typescript.map(|ts| &ts.pnpm_presets);
+				$body
+			}
+			PresetKind::ComposeFile => {
+				let $store = docker.map(|docker| &docker.compose_presets);
+				$body
+			}
+			PresetKind::DockerService => {
+				let $store = docker.map(|docker| &docker.service_presets);
+				$body
+			}
+			PresetKind::Templates => {
+				let $store = Some(&config.templating_presets);
+				$body
+			}
+			PresetKind::PreCommit => {
+				let $store = Some(&config.pre_commit_presets);
+				$body
+			}
+			PresetKind::Gitignore => {
+				let $store = Some(&config.gitignore_presets);
+				$body
+			}
+			PresetKind::CargoToml => {
+				let $store = Some(&config.rust.manifest_presets);
+				$body
+			}
+			PresetKind::GithubWorkflow => {
+				let $store = Some(&config.github.workflow_presets);
+				$body
+			}
+			PresetKind::GithubWorkflowJob => {
+				let $store = Some(&config.github.workflow_job_presets);
+				$body
+			}
+			PresetKind::TsPackage => {
+				let $other_store = typescript.map(|ts| &ts.package_presets);
+				$other_body
+			}
+			PresetKind::Vitest => {
+				let $other_store = typescript.map(|ts| &ts.vitest_presets);
+				$other_body
+			}
+			PresetKind::Repo => {
+				let $other_store = Some(&config.repo_presets);
+				$other_body
+			}
+			PresetKind::RustCrate => {
+				let $other_store = Some(&config.rust.crate_presets);
+				$other_body
+			}
+			PresetKind::GithubWorkflowStep => {
+				let $other_store = Some(&config.github.steps_presets);
+				$other_body
+			}
+		}
+	}};
+}
+
+/// Evaluates an expression with the map that contains the presets of a given kind, if they can extend other presets.
+macro_rules! with_extensible_presets {
+	($config:expr, $kind:expr, |$store:ident| $body:expr) => {{
+		let kind = $kind;
+
+		$crate::config::presets::with_preset_store!(
+			$config,
+			kind,
+			|store| {
+				let empty = IndexMap::new();
+				let $store = store.unwrap_or(&empty);
+				$body
+			},
+			|_store| Err(anyhow!("{kind:?} presets cannot extend other presets").into())
+		)
+	}};
+}
+
+pub(super) use {with_extensible_presets, with_preset_store};
+
+/// The keys of the map that contains the presets of a given kind, in a config file.
+pub(crate) const fn preset_map_path(kind: PresetKind) -> &'static [&'static str] {
+	match kind {
+		PresetKind::PackageJson => &["typescript", "package_json_presets"],
+		PresetKind::TsPackage => &["typescript", "package_presets"],
+		PresetKind::TsConfig => &["typescript", "ts_config_presets"],
+		PresetKind::Oxlint => &["typescript", "oxlint_presets"],
+		PresetKind::PnpmWorkspace => &["typescript", "pnpm_presets"],
+		PresetKind::Vitest => &["typescript", "vitest_presets"],
+		PresetKind::ComposeFile => &["docker", "compose_presets"],
+		PresetKind::DockerService => &["docker", "service_presets"],
+		PresetKind::Templates => &["templating_presets"],
+		PresetKind::PreCommit => &["pre_commit_presets"],
+		PresetKind::Repo => &["repo_presets"],
+		PresetKind::Gitignore => &["gitignore_presets"],
+		PresetKind::CargoToml => &["rust", "manifest_presets"],
+		PresetKind::RustCrate => &["rust", "crate_presets"],
+		PresetKind::GithubWorkflow => &["github", "workflow_presets"],
+		PresetKind::GithubWorkflowJob => &["github", "workflow_job_presets"],
+		PresetKind::GithubWorkflowStep => &["github", "steps_presets"],
+	}
+}
+
+impl Config {
+	/// Returns the ids of all the presets of a given kind.
+	pub fn preset_ids(&self, kind: PresetKind) -> Vec<&str> {
+		with_preset_store!(self, kind, |store| {
+			store.map_or_else(Vec::new, |store| store.keys().map(String::as_str).collect())
+		})
+	}
+
+	/// Resolves a preset and returns its contents, in the format of the file that it would generate.
+	///
+	/// Presets that are specific to `sketch` (like repo or templating presets) are shown as yaml.
+	pub fn format_preset(&self, kind: PresetKind, id: &str) -> AppResult<String> {
+		let typescript = self.typescript.clone().unwrap_or_default();
+		let docker = self.docker.clone().unwrap_or_default();
+
+		let output = match kind {
+			PresetKind::PackageJson => {
+				Format::Json.serialize(&typescript.get_package_json(id)?.config)?
+			}
+			PresetKind::TsConfig => {
+				Format::Json.serialize(&typescript.get_tsconfig_preset(id)?.config)?
+			}
+			PresetKind::Oxlint => {
+				Format::Json.serialize(&typescript.get_oxlint_preset(id)?.config)?
+			}
+			PresetKind::TsPackage => Format::Yaml.serialize(&typescript.get_package_preset(id)?)?,
+			PresetKind::PnpmWorkspace => {
+				Format::Yaml.serialize(&typescript.get_pnpm_preset(id)?.config)?
+			}
+			PresetKind::Vitest => Format::Yaml.serialize(&typescript.get_vitest_preset(id)?)?,
+			PresetKind::ComposeFile => Format::Yaml.serialize(
+				&docker
+					.get_file_preset(id)?
+					.process_data(id, &docker)?,
+			)?,
+			PresetKind::DockerService => {
+				Format::Yaml.serialize(&docker.get_service_preset(id)?.config)?
+			}
+			PresetKind::Templates => Format::Yaml.serialize(&self.get_templating_preset(id)?)?,
+			PresetKind::PreCommit => {
+				Format::Yaml.serialize(&self.get_pre_commit_preset(id)?.config)?
+			}
+			PresetKind::Repo => Format::Yaml.serialize(&self.get_repo_preset(id)?)?,
+			PresetKind::Gitignore => self.get_gitignore_preset(id)?.content.to_string(),
+			PresetKind::CargoToml => self
+				.rust
+				.get_cargo_toml_preset(id)?
+				.config
+				.as_document()
+				.to_string(),
+			PresetKind::RustCrate => Format::Yaml.serialize(
+				&self
+					.rust
+					.get_crate_preset(id)?
+					.process_data(self)?,
+			)?,
+			PresetKind::GithubWorkflow => Format::Yaml.serialize(&self.github.get_workflow(id)?)?,
+			PresetKind::GithubWorkflowJob => {
+				let preset = self
+					.github
+					.workflow_job_presets
+					.get(id)
+					.ok_or_else(|| {
+						AppError::preset_not_found(
+							kind,
+							id,
+							self.github.workflow_job_presets.keys(),
+						)
+					})?
+					.clone();
+
+				Format::Yaml.serialize(&self.github.process_gh_job_preset(id, preset)?)?
+			}
+			PresetKind::GithubWorkflowStep => {
+				Format::Yaml.serialize(self.github.steps_presets.get(id).ok_or_else(|| {
+					AppError::preset_not_found(kind, id, self.github.steps_presets.keys())
+				})?)?
+			}
+		};
+
+		Ok(output)
+	}
+
+	/// Resolves a preset that can extend other presets, and returns its content as a generic value.
+	pub fn resolve_preset(&self, kind: PresetKind, id: &str) -> AppResult<Value> {
+		with_extensible_presets!(self, kind, |store| resolve_extensible_preset(id, store))
+	}
+}
+
+fn resolve_extensible_preset<T: ExtensiblePreset + Serialize>(
+	id: &str,
+	store: &IndexMap<String, T>,
+) -> AppResult<Value> {
+	let preset = get_extensible_preset(id, store)?.merge_presets(id, store)?;
+
+	preset_content(id, &preset)
+}
+
+pub(super) fn get_extensible_preset<T: ExtensiblePreset>(
+	id: &str,
+	store: &IndexMap<String, T>,
+) -> AppResult<T> {
+	store
+		.get(id)
+		.cloned()
+		.ok_or_else(|| AppError::preset_not_found(T::kind(), id, store.keys()))
+}
+
+/// Serializes a preset without its `extends_presets` field and the fields that are not set.
+pub(super) fn preset_content<T: Serialize>(id: &str, preset: &T) -> AppResult<Value> {
+	let mut value = serde_json::to_value(preset)
+		.with_context(|| format!("Failed to serialize the preset `{id}`"))?;
+
+	if let Value::Object(map) = &mut value {
+		map.shift_remove("extends_presets");
+	}
+
+	remove_nulls(&mut value);
+
+	Ok(value)
+}
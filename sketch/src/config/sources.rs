@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+use super::*;
+
+/// Keeps track of the config files where each preset, template and variable was defined.
+///
+/// Each entry lists the files in order of priority, so the last file is the one whose definition is in use, while the others have been overridden.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigSources {
+	/// The config files that have been loaded, from the lowest to the highest priority.
+	pub files: Vec<PathBuf>,
+	pub presets: IndexMap<(PresetKind, String), Vec<PathBuf>>,
+	pub templates: IndexMap<String, Vec<PathBuf>>,
+	pub vars: IndexMap<String, Vec<PathBuf>>,
+}
+
+impl Merge for ConfigSources {
+	fn merge(&mut self, other: Self) {
+		for file in other.files {
+			self.files.retain(|f| f != &file);
+			self.files.push(file);
+		}
+
+		merge_entries(&mut self.presets, other.presets);
+		merge_entries(&mut self.templates, other.templates);
+		merge_entries(&mut self.vars, other.vars);
+	}
+}
+
+fn merge_entries<K: std::hash::Hash + Eq>(
+	target: &mut IndexMap<K, Vec<PathBuf>>,
+	source: IndexMap<K, Vec<PathBuf>>,
+) {
+	for (key, files) in source {
+		target.entry(key).or_default().extend(files);
+	}
+}
+
+impl ConfigSources {
+	/// Records the items defined in a single config file.
+	pub(crate) fn from_file(config: &Config, file: &Path) -> Self {
+		let mut sources = Self {
+			files: vec![file.to_path_buf()],
+			..Default::default()
+		};
+
+		for kind in PresetKind::value_variants() {
+			for id in config.preset_ids(*kind) {
+				sources
+					.presets
+					.insert((*kind, id.to_string()), vec![file.to_path_buf()]);
+			}
+		}
+
+		for name in config.templates.keys() {
+			sources
+				.templates
+				.insert(name.clone(), vec![file.to_path_buf()]);
+		}
+
+		sources.add_vars(config.vars.keys(), file);
+
+		sources
+	}
+
+	pub(crate) fn add_vars<'a>(
+		&mut self,
+		names: impl IntoIterator<Item = &'a String>,
+		file: &Path,
+	) {
+		for name in names {
+			self.vars
+				.entry(name.clone())
+				.or_default()
+				.push(file.to_path_buf());
+		}
+	}
+
+	/// Returns the config file that contains the active definition of a preset.
+	pub fn preset_source(&self, kind: PresetKind, id: &str) -> Option<&Path> {
+		self.presets
+			.get(&(kind, id.to_string()))
+			.and_then(|files| files.last())
+			.map(|file| file.as_path())
+	}
+
+	/// Returns the config file that contains the active definition of a template.
+	pub fn template_source(&self, name: &str) -> Option<&Path> {
+		self.templates
+			.get(name)
+			.and_then(|files| files.last())
+			.map(|file| file.as_path())
+	}
+
+	/// Returns the file that contains the active definition of a variable.
+	pub fn var_source(&self, name: &str) -> Option<&Path> {
+		self.vars
+			.get(name)
+			.and_then(|files| files.last())
+			.map(|file| file.as_path())
+	}
+
+	/// Adds the information about the source files to the errors that refer to presets.
+	pub(crate) fn annotate(&self, error: AppError) -> AppError {
+		match error {
+			AppError::PresetNotFound { .. } if !self.files.is_empty() => {
+				AppError::NotFoundInSources {
+					source: Box::new(error),
+					files: self.files.clone(),
+				}
+			}
+			AppError::CircularDependency(cycle) => {
+				AppError::CircularDependency(self.annotate_cycle(cycle))
+			}
+			AppError::InvalidConfig(issues) => AppError::InvalidConfig(
+				issues
+					.into_iter()
+					.map(|mut issue| {
+						if let AppError::CircularDependency(cycle) = issue.error {
+							issue.error = AppError::CircularDependency(self.annotate_cycle(cycle));
+						}
+
+						issue
+					})
+					.collect(),
+			),
+			_ => error,
+		}
+	}
+
+	fn annotate_cycle(&self, mut cycle: DependencyCycle) -> DependencyCycle {
+		if let Some(kind) = cycle.kind {
+			cycle.sources = cycle
+				.chain
+				.iter()
+				.map(|id| {
+					self.preset_source(kind, id)
+						.map(Path::to_path_buf)
+				})
+				.collect();
+		}
+
+		cycle
+	}
+}
+
+fn format_files(files: &[PathBuf]) -> String {
+	let (active, overridden) = files
+		.split_last()
+		.expect("Source entries are never empty");
+
+	if overridden.is_empty() {
+		return format!("`{}`", active.display());
+	}
+
+	let overridden: Vec<String> = overridden
+		.iter()
+		.rev()
+		.map(|file| format!("`{}`", file.display()))
+		.collect();
+
+	format!(
+		"`{}` (overrides {})",
+		active.display(),
+		overridden.join(", ")
+	)
+}
+
+impl Display for ConfigSources {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Config files (from lowest to highest priority):")?;
+
+		for file in &self.files {
+			writeln!(f, "  - `{}`", file.display())?;
+		}
+
+		if !self.presets.is_empty() {
+			writeln!(f, "Presets:")?;
+
+			for ((kind, id), files) in &self.presets {
+				writeln!(f, "  - {kind:?} `{id}`: {}", format_files(files))?;
+			}
+		}
+
+		if !self.templates.is_empty() {
+			writeln!(f, "Templates:")?;
+
+			for (name, files) in &self.templates {
+				writeln!(f, "  - `{name}`: {}", format_files(files))?;
+			}
+		}
+
+		if !self.vars.is_empty() {
+			writeln!(f, "Vars:")?;
+
+			for (name, files) in &self.vars {
+				writeln!(f, "  - `{name}`: {}", format_files(files))?;
+			}
+		}
+
+		Ok(())
+	}
+}
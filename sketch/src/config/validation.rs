@@ -0,0 +1,397 @@
+use super::*;
+use crate::{
+	docker::ServicePresetRef,
+	gh_workflow::{GhJobPreset, GhJobPresetRef, GhWorkflowPresetRef, Job, StepPresetRef},
+	init_repo::{gitignore::GitIgnorePresetRef, pre_commit::PreCommitSetting},
+	rust::CargoTomlPresetRef,
+	ts::{
+		oxlint::OxlintPresetRef, package::TsPackagePreset, package_json::PackageJsonPresetRef,
+		ts_config::TsConfigPresetRef, vitest::VitestPresetRef,
+	},
+};
+
+impl Config {
+	/// Checks every preset in the config, along with the presets that they reference or extend.
+	///
+	/// Unlike the preset getters, this does not stop at the first error, and returns an [`AppError::InvalidConfig`] with all of the issues found in the config.
+	pub fn validate(&self) -> AppResult {
+		let mut validator = Validator::default();
+
+		validator.check_extensible("templating_presets", &self.templating_presets);
+		validator.check_extensible("gitignore_presets", &self.gitignore_presets);
+		validator.check_extensible("pre_commit_presets", &self.pre_commit_presets);
+
+		for (id, preset) in &self.repo_presets {
+			let location = format!("repo_presets.{id}");
+
+			if let Some(gitignore) = &preset.gitignore {
+				validator.check_gitignore_ref(&location, gitignore, &self.gitignore_presets);
+			}
+
+			match &preset.pre_commit {
+				Some(PreCommitSetting::Id(id)) => {
+					validator.check_ref(&location, id, &self.pre_commit_presets);
+				}
+				Some(PreCommitSetting::Config(inlined)) => {
+					validator.check_inlined(&location, inlined, &self.pre_commit_presets);
+				}
+				_ => {}
+			};
+
+			validator.check_templating_refs(&location, &preset.with_templates, self);
+
+			for workflow in &preset.workflows {
+				match workflow {
+					GhWorkflowPresetRef::PresetId { id, .. } => {
+						validator.check_ref(&location, id, &self.github.workflow_presets);
+					}
+					GhWorkflowPresetRef::Preset { workflow, .. } => {
+						validator.check_inlined(&location, workflow, &self.github.workflow_presets);
+						validator.check_workflow_jobs(
+							&location,
+							&workflow.config.jobs,
+							&self.github,
+						);
+					}
+				}
+			}
+		}
+
+		self.check_rust_config(&mut validator);
+
+		if let Some(typescript) = &self.typescript {
+			validator.check_typescript_config(typescript, self);
+		}
+
+		if let Some(docker) = &self.docker {
+			validator.check_extensible("docker.service_presets", &docker.service_presets);
+			validator.check_extensible("docker.compose_presets", &docker.compose_presets);
+
+			for (id, preset) in &docker.compose_presets {
+				let location = format!("docker.compose_presets.{id}");
+
+				for service in preset.config.services.values() {
+					match service {
+						ServicePresetRef::PresetId(service_id) => {
+							validator.check_ref(&location, service_id, &docker.service_presets);
+						}
+						ServicePresetRef::Preset(inlined) => {
+							validator.check_inlined(
+								&location,
+								inlined.as_ref(),
+								&docker.service_presets,
+							);
+						}
+					}
+				}
+			}
+		}
+
+		validator.check_extensible("github.workflow_presets", &self.github.workflow_presets);
+		validator.check_extensible(
+			"github.workflow_job_presets",
+			&self.github.workflow_job_presets,
+		);
+
+		for (id, preset) in &self.github.workflow_presets {
+			validator.check_workflow_jobs(
+				&format!("github.workflow_presets.{id}"),
+				&preset.config.jobs,
+				&self.github,
+			);
+		}
+
+		for (id, preset) in &self.github.workflow_job_presets {
+			validator.check_job_steps(
+				&format!("github.workflow_job_presets.{id}"),
+				preset,
+				&self.github,
+			);
+		}
+
+		validator.finish()
+	}
+
+	fn check_rust_config(&self, validator: &mut Validator) {
+		validator.check_extensible("rust.manifest_presets", &self.rust.manifest_presets);
+
+		for (id, preset) in &self.rust.crate_presets {
+			let location = format!("rust.crate_presets.{id}");
+
+			match &preset.manifest {
+				CargoTomlPresetRef::PresetId(manifest_id) => {
+					validator.check_ref(&location, manifest_id, &self.rust.manifest_presets);
+				}
+				CargoTomlPresetRef::Preset(inlined) => {
+					validator.check_inlined(&location, inlined, &self.rust.manifest_presets);
+				}
+			};
+
+			if let Some(gitignore) = &preset.gitignore {
+				validator.check_gitignore_ref(&location, gitignore, &self.gitignore_presets);
+			}
+
+			validator.check_templating_refs(&location, &preset.with_templates, self);
+		}
+	}
+}
+
+#[derive(Default)]
+struct Validator {
+	issues: Vec<ConfigIssue>,
+}
+
+impl Validator {
+	fn finish(self) -> AppResult {
+		if self.issues.is_empty() {
+			Ok(())
+		} else {
+			Err(AppError::InvalidConfig(self.issues))
+		}
+	}
+
+	fn push(&mut self, location: &str, error: AppError) {
+		self.issues.push(ConfigIssue {
+			location: location.to_string(),
+			error,
+		});
+	}
+
+	fn check_ref<T: ExtensiblePreset>(
+		&mut self,
+		location: &str,
+		id: &str,
+		store: &IndexMap<String, T>,
+	) {
+		if !store.contains_key(id) {
+			self.push(
+				location,
+				AppError::preset_not_found(T::kind(), id, store.keys()),
+			);
+		}
+	}
+
+	fn check_inlined<T: ExtensiblePreset>(
+		&mut self,
+		location: &str,
+		preset: &T,
+		store: &IndexMap<String, T>,
+	) {
+		for id in preset.extended_preset_ids() {
+			self.check_ref(location, id, store);
+		}
+	}
+
+	/// Checks that all the extended presets exist, and reports every circular chain of presets.
+	fn check_extensible<T: ExtensiblePreset>(
+		&mut self,
+		section: &str,
+		store: &IndexMap<String, T>,
+	) {
+		let graph: IndexMap<&str, IndexSet<String>> = store
+			.iter()
+			.map(|(id, preset)| (id.as_str(), preset.extended_preset_ids().clone()))
+			.collect();
+
+		for (id, extended) in &graph {
+			for target in extended {
+				if !graph.contains_key(target.as_str()) {
+					self.push(
+						&format!("{section}.{id}"),
+						AppError::preset_not_found(T::kind(), target, store.keys()),
+					);
+				}
+			}
+		}
+
+		let mut done: IndexSet<&str> = IndexSet::new();
+
+		for id in graph.keys() {
+			self.find_cycles::<T>(section, id, &graph, &mut Vec::new(), &mut done);
+		}
+	}
+
+	fn find_cycles<'a, T: ExtensiblePreset>(
+		&mut self,
+		section: &str,
+		id: &'a str,
+		graph: &IndexMap<&'a str, IndexSet<String>>,
+		path: &mut Vec<&'a str>,
+		done: &mut IndexSet<&'a str>,
+	) {
+		if done.contains(id) {
+			return;
+		}
+
+		if let Some(start) = path.iter().position(|item| *item == id) {
+			let mut cycle = path[start..].to_vec();
+			cycle.push(id);
+
+			self.push(
+				&format!("{section}.{id}"),
+				AppError::CircularDependency(DependencyCycle::new(
+					Some(T::kind()),
+					cycle.into_iter().map(str::to_string).collect(),
+				)),
+			);
+
+			return;
+		}
+
+		path.push(id);
+
+		for target in &graph[id] {
+			if let Some((target_id, _)) = graph.get_key_value(target.as_str()) {
+				self.find_cycles::<T>(section, target_id, graph, path, done);
+			}
+		}
+
+		path.pop();
+		done.insert(id);
+	}
+
+	fn check_gitignore_ref(
+		&mut self,
+		location: &str,
+		preset_ref: &GitIgnorePresetRef,
+		store: &IndexMap<String, GitignorePreset>,
+	) {
+		match preset_ref {
+			GitIgnorePresetRef::PresetId(id) => self.check_ref(location, id, store),
+			GitIgnorePresetRef::Preset(inlined) => self.check_inlined(location, inlined, store),
+		};
+	}
+
+	fn check_templating_refs(
+		&mut self,
+		location: &str,
+		refs: &[TemplatingPresetRef],
+		config: &Config,
+	) {
+		for preset_ref in refs {
+			match preset_ref {
+				TemplatingPresetRef::PresetId { preset_id, .. } => {
+					self.check_ref(location, preset_id, &config.templating_presets);
+				}
+				TemplatingPresetRef::Preset(inlined) => {
+					self.check_inlined(location, inlined, &config.templating_presets);
+				}
+			}
+		}
+	}
+
+	fn check_workflow_jobs(
+		&mut self,
+		location: &str,
+		jobs: &IndexMap<String, GhJobPresetRef>,
+		github: &GithubConfig,
+	) {
+		for job in jobs.values() {
+			match job {
+				GhJobPresetRef::PresetId(id) => {
+					self.check_ref(location, id, &github.workflow_job_presets);
+				}
+				GhJobPresetRef::Preset(inlined) => {
+					self.check_inlined(location, inlined.as_ref(), &github.workflow_job_presets);
+					self.check_job_steps(location, inlined, github);
+				}
+			}
+		}
+	}
+
+	fn check_job_steps(&mut self, location: &str, job: &GhJobPreset, github: &GithubConfig) {
+		if let Job::Normal(job) = &job.job {
+			for step in &job.steps {
+				if let StepPresetRef::PresetId(id) = step
+					&& !github.steps_presets.contains_key(id)
+				{
+					self.push(
+						location,
+						AppError::preset_not_found(
+							PresetKind::GithubWorkflowStep,
+							id,
+							github.steps_presets.keys(),
+						),
+					);
+				}
+			}
+		}
+	}
+
+	fn check_typescript_config(&mut self, typescript: &TypescriptConfig, config: &Config) {
+		self.check_extensible(
+			"typescript.package_json_presets",
+			&typescript.package_json_presets,
+		);
+		self.check_extensible(
+			"typescript.ts_config_presets",
+			&typescript.ts_config_presets,
+		);
+		self.check_extensible("typescript.oxlint_presets", &typescript.oxlint_presets);
+		self.check_extensible("typescript.pnpm_presets", &typescript.pnpm_presets);
+
+		for (id, preset) in &typescript.package_presets {
+			self.check_ts_package(
+				&format!("typescript.package_presets.{id}"),
+				preset,
+				typescript,
+				config,
+			);
+		}
+	}
+
+	fn check_ts_package(
+		&mut self,
+		location: &str,
+		preset: &TsPackagePreset,
+		typescript: &TypescriptConfig,
+		config: &Config,
+	) {
+		for tsconfig in &preset.ts_config {
+			match &tsconfig.config {
+				Some(TsConfigPresetRef::PresetId(id)) => {
+					self.check_ref(location, id, &typescript.ts_config_presets);
+				}
+				Some(TsConfigPresetRef::Preset(inlined)) => {
+					self.check_inlined(location, inlined, &typescript.ts_config_presets);
+				}
+				None => {}
+			};
+		}
+
+		match &preset.package_json {
+			Some(PackageJsonPresetRef::PresetId(id)) => {
+				self.check_ref(location, id, &typescript.package_json_presets);
+			}
+			Some(PackageJsonPresetRef::Preset(inlined)) => {
+				self.check_inlined(location, inlined, &typescript.package_json_presets);
+			}
+			None => {}
+		};
+
+		match &preset.oxlint {
+			Some(OxlintPresetRef::PresetId(id)) => {
+				self.check_ref(location, id, &typescript.oxlint_presets);
+			}
+			Some(OxlintPresetRef::Preset(inlined)) => {
+				self.check_inlined(location, inlined, &typescript.oxlint_presets);
+			}
+			_ => {}
+		};
+
+		if let Some(VitestPresetRef::PresetId(id)) = &preset.vitest
+			&& !typescript.vitest_presets.contains_key(id)
+		{
+			self.push(
+				location,
+				AppError::preset_not_found(
+					PresetKind::Vitest,
+					id,
+					typescript.vitest_presets.keys(),
+				),
+			);
+		}
+
+		self.check_templating_refs(location, &preset.with_templates, config);
+	}
+}
@@ -0,0 +1,431 @@
+use serde_json::Map;
+
+use super::*;
+
+/// The version of the config format used by this version of `sketch`.
+pub const CONFIG_VERSION: &str = "0.6";
+
+/// The result of the migration of a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+	/// The version of the original config, if it was set.
+	pub from_version: Option<String>,
+	/// The changes that have been applied to the config.
+	pub changes: Vec<String>,
+	/// The items that have been removed because they are no longer supported, and must be migrated manually.
+	pub removed: Vec<String>,
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64)> {
+	let mut segments = version.trim_start_matches('v').split('.');
+
+	let major = segments.next()?.parse().ok()?;
+	let minor = segments.next().unwrap_or("0").parse().ok()?;
+
+	Some((major, minor))
+}
+
+fn is_newer(version: &str) -> bool {
+	parse_version(version)
+		.zip(parse_version(CONFIG_VERSION))
+		.is_some_and(|(version, current)| version > current)
+}
+
+fn get_version(raw_config: &Value) -> Option<&str> {
+	raw_config.get("version").and_then(Value::as_str)
+}
+
+/// Provides a clearer error if a config file failed to deserialize because it targets a different version of `sketch`.
+pub(crate) fn check_version_on_error(
+	raw_config: Option<&Value>,
+	file: &Path,
+	error: AppError,
+) -> AppError {
+	let Some(raw_config) = raw_config else {
+		return error;
+	};
+
+	match get_version(raw_config) {
+		Some(version) if is_newer(version) => AppError::NewerConfigVersion {
+			file: file.to_path_buf(),
+			version: version.to_string(),
+		},
+		_ if needs_migration(raw_config) => match error {
+			AppError::DeserializationError {
+				file,
+				error,
+				location,
+			} => AppError::DeserializationError {
+				error: format!(
+					"{error}\nThis config file seems to use an older format. You can update it with `sketch config migrate {}`",
+					file.display()
+				),
+				file,
+				location,
+			},
+			_ => error,
+		},
+		_ => error,
+	}
+}
+
+impl Config {
+	/// Prints a warning if the config targets a newer version of `sketch`.
+	pub(crate) fn check_version(&self, file: &Path) {
+		if let Some(version) = &self.version
+			&& is_newer(version)
+		{
+			emit(OutputEvent::Warning {
+				message: format!(
+					"the config file `{}` targets sketch v{version}, but the installed version is v{}",
+					file.display(),
+					env!("CARGO_PKG_VERSION")
+				),
+			});
+		}
+	}
+}
+
+fn needs_migration(raw_config: &Value) -> bool {
+	match get_version(raw_config) {
+		Some(version) => parse_version(version)
+			.zip(parse_version(CONFIG_VERSION))
+			.is_some_and(|(version, current)| version < current),
+		None => {
+			raw_config.get("git_presets").is_some()
+				|| raw_config.get("cargo_toml_presets").is_some()
+		}
+	}
+}
+
+/// Rewrites a config file that uses an older format so that it uses the current one, keeping the same file format.
+pub fn migrate_config_file(path: &Path) -> AppResult<MigrationReport> {
+	let format = Format::from_path(path)?;
+
+	let mut raw_config: Value = match format {
+		Format::Yaml => deserialize_yaml(path)?,
+		Format::Toml => deserialize_toml(path)?,
+		Format::Json => deserialize_json(path)?,
+	};
+
+	let from_version = get_version(&raw_config).map(str::to_string);
+
+	if let Some(version) = &from_version
+		&& is_newer(version)
+	{
+		return Err(AppError::NewerConfigVersion {
+			file: path.to_path_buf(),
+			version: version.clone(),
+		});
+	}
+
+	let Value::Object(map) = &mut raw_config else {
+		return Err(anyhow!(
+			"The config file `{}` does not contain a map",
+			path.display()
+		)
+		.into());
+	};
+
+	let mut report = MigrationReport {
+		from_version,
+		changes: Vec::new(),
+		removed: Vec::new(),
+	};
+
+	migrate_to_v0_6(map, &mut report)?;
+
+	// Placing the version at the top of the file
+	map.shift_remove("version");
+	map.shift_insert(
+		0,
+		"version".to_string(),
+		Value::String(CONFIG_VERSION.to_string()),
+	);
+
+	format.serialize_to_file(&raw_config, path, true)?;
+
+	Ok(report)
+}
+
+fn rename_key(map: &mut Map<String, Value>, from: &str, to: &str) -> bool {
+	if map.contains_key(to) {
+		return false;
+	}
+
+	if let Some(idx) = map.keys().position(|key| key == from)
+		&& let Some(value) = map.shift_remove(from)
+	{
+		map.shift_insert(idx, to.to_string(), value);
+		return true;
+	}
+
+	false
+}
+
+/// Adds the entries of a map to another one. Fails if the same key is present in both maps, since only one of the values could be kept.
+fn merge_entries(
+	target: &mut Map<String, Value>,
+	source: Value,
+	source_location: &str,
+	target_location: &str,
+) -> AppResult {
+	let Value::Object(source) = source else {
+		return Err(anyhow!("`{source_location}` must be a map").into());
+	};
+
+	if let Some(key) = source
+		.keys()
+		.find(|key| target.contains_key(*key))
+	{
+		return Err(anyhow!(
+			"Cannot move `{source_location}` to `{target_location}`: the key `{key}` is present in both. One of them must be renamed or removed manually"
+		)
+		.into());
+	}
+
+	target.extend(source);
+
+	Ok(())
+}
+
+/// Moves the entries of a top level map to another map, which is created if it does not exist.
+fn move_entries(
+	map: &mut Map<String, Value>,
+	from: &str,
+	to: &[&str],
+	report: &mut MigrationReport,
+) -> AppResult {
+	let Some(entries) = map.shift_remove(from) else {
+		return Ok(());
+	};
+
+	let to_location = to.join(".");
+
+	if to.len() == 1 && !map.contains_key(to[0]) {
+		map.insert(to[0].to_string(), entries);
+		report
+			.changes
+			.push(format!("Renamed `{from}` to `{to_location}`"));
+		return Ok(());
+	}
+
+	let mut target = &mut *map;
+
+	for key in to {
+		target = target
+			.entry(*key)
+			.or_insert_with(|| Value::Object(Map::new()))
+			.as_object_mut()
+			.with_context(|| format!("`{key}` must be a map"))?;
+	}
+
+	merge_entries(target, entries, from, &to_location)?;
+
+	report
+		.changes
+		.push(format!("Moved `{from}` to `{to_location}`"));
+
+	Ok(())
+}
+
+fn presets_mut<'a>(
+	map: &'a mut Map<String, Value>,
+	path: &[&str],
+) -> impl Iterator<Item = (&'a String, &'a mut Map<String, Value>)> {
+	let mut node = Some(map);
+
+	for key in path {
+		node = node.and_then(|node| node.get_mut(*key)?.as_object_mut());
+	}
+
+	node.into_iter().flat_map(|presets| {
+		presets
+			.iter_mut()
+			.filter_map(|(id, preset)| Some((id, preset.as_object_mut()?)))
+	})
+}
+
+fn migrate_to_v0_6(map: &mut Map<String, Value>, report: &mut MigrationReport) -> AppResult {
+	move_entries(map, "git_presets", &["repo_presets"], report)?;
+	move_entries(
+		map,
+		"cargo_toml_presets",
+		&["rust", "manifest_presets"],
+		report,
+	)?;
+
+	for (id, preset) in presets_mut(map, &["repo_presets"]) {
+		for field in ["hooks_pre", "hooks_post"] {
+			if preset.shift_remove(field).is_some() {
+				report
+					.removed
+					.push(format!("repo_presets.{id}.{field}"));
+			}
+		}
+	}
+
+	if let Some(Value::Object(typescript)) = map.get_mut("typescript")
+		&& typescript.shift_remove("people").is_some()
+	{
+		report
+			.removed
+			.push("typescript.people".to_string());
+	}
+
+	for (id, preset) in presets_mut(map, &["rust", "manifest_presets"]) {
+		migrate_manifest(preset, &format!("rust.manifest_presets.{id}"), report);
+	}
+
+	for (id, preset) in presets_mut(map, &["typescript", "oxlint_presets"]) {
+		// The extra fields are now placed directly in the preset
+		if let Some(extras) = preset.shift_remove("extras") {
+			let location = format!("typescript.oxlint_presets.{id}");
+
+			merge_entries(preset, extras, &format!("{location}.extras"), &location)?;
+
+			report
+				.changes
+				.push(format!("Moved `{location}.extras` to `{location}`"));
+		}
+	}
+
+	for (id, preset) in presets_mut(map, &["templating_presets"]) {
+		remove_templates_context(preset, &format!("templating_presets.{id}"), report);
+	}
+
+	let mut renamed_refs = 0;
+
+	for value in map.values_mut() {
+		rename_templating_refs(value, &mut renamed_refs);
+	}
+
+	if renamed_refs > 0 {
+		report.changes.push(format!(
+			"Renamed `id` to `preset_id` in {renamed_refs} templating preset reference(s)"
+		));
+	}
+
+	Ok(())
+}
+
+/// Removes the fields of a `Cargo.toml` preset that are no longer supported.
+fn migrate_manifest(
+	manifest: &mut Map<String, Value>,
+	location: &str,
+	report: &mut MigrationReport,
+) {
+	if let Some(lints) = manifest
+		.get_mut("workspace")
+		.and_then(|workspace| workspace.get_mut("lints"))
+	{
+		migrate_lints(lints, &format!("{location}.workspace.lints"), report);
+	}
+
+	if let Some(lints) = manifest.get_mut("lints") {
+		migrate_lints(lints, &format!("{location}.lints"), report);
+	}
+
+	if let Some(lib) = manifest.get_mut("lib") {
+		remove_product_fields(lib, &format!("{location}.lib"), report);
+	}
+
+	for kind in ["bin", "example", "test", "bench"] {
+		if let Some(Value::Array(products)) = manifest.get_mut(kind) {
+			for (i, product) in products.iter_mut().enumerate() {
+				remove_product_fields(product, &format!("{location}.{kind}[{i}]"), report);
+			}
+		}
+	}
+}
+
+fn migrate_lints(lints: &mut Value, location: &str, report: &mut MigrationReport) {
+	let Value::Object(lints) = lints else {
+		return;
+	};
+
+	for (tool, tool_lints) in lints.iter_mut() {
+		let Value::Object(tool_lints) = tool_lints else {
+			continue;
+		};
+
+		for (name, lint) in tool_lints.iter_mut() {
+			let Value::Object(lint) = lint else {
+				continue;
+			};
+
+			let lint_location = format!("{location}.{tool}.{name}");
+
+			if lint.shift_remove("config").is_some() {
+				report
+					.removed
+					.push(format!("{lint_location}.config"));
+			}
+
+			// `force-warn` is no longer supported as a lint level, and the closest one is `warn`
+			if let Some(level) = lint.get_mut("level")
+				&& level.as_str() == Some("force-warn")
+			{
+				*level = Value::String("warn".to_string());
+				report.changes.push(format!(
+					"Changed the level of `{lint_location}` from `force-warn` to `warn`"
+				));
+			}
+		}
+	}
+}
+
+fn remove_product_fields(product: &mut Value, location: &str, report: &mut MigrationReport) {
+	if let Value::Object(product) = product {
+		for field in ["edition", "plugin"] {
+			if product.shift_remove(field).is_some() {
+				report.removed.push(format!("{location}.{field}"));
+			}
+		}
+	}
+}
+
+fn remove_templates_context(
+	preset: &mut Map<String, Value>,
+	location: &str,
+	report: &mut MigrationReport,
+) {
+	if let Some(Value::Array(templates)) = preset.get_mut("templates") {
+		for (i, template) in templates.iter_mut().enumerate() {
+			if let Value::Object(template) = template
+				&& template.contains_key("output")
+				&& template.shift_remove("context").is_some()
+			{
+				report
+					.removed
+					.push(format!("{location}.templates[{i}].context"));
+			}
+		}
+	}
+}
+
+fn rename_templating_refs(value: &mut Value, count: &mut usize) {
+	match value {
+		Value::Object(map) => {
+			if let Some(Value::Array(refs)) = map.get_mut("with_templates") {
+				for preset_ref in refs.iter_mut() {
+					if let Value::Object(preset_ref) = preset_ref
+						&& rename_key(preset_ref, "id", "preset_id")
+					{
+						*count += 1;
+					}
+				}
+			}
+
+			for item in map.values_mut() {
+				rename_templating_refs(item, count);
+			}
+		}
+		Value::Array(items) => {
+			for item in items {
+				rename_templating_refs(item, count);
+			}
+		}
+		_ => {}
+	}
+}
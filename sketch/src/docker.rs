@@ -19,9 +19,8 @@ impl DockerConfig {
 		Ok(self
 			.compose_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::ComposeFile,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::ComposeFile, id, self.compose_presets.keys())
 			})?
 			.clone())
 	}
@@ -29,9 +28,12 @@ impl DockerConfig {
 	pub fn get_service_preset(&self, id: &str) -> AppResult<DockerServicePreset> {
 		self.service_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::DockerService,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(
+					PresetKind::DockerService,
+					id,
+					self.service_presets.keys(),
+				)
 			})?
 			.clone()
 			.merge_presets(id, &self.service_presets)
@@ -59,6 +61,10 @@ impl ExtensiblePreset for ComposeFilePreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 impl ComposeFilePreset {
@@ -78,7 +84,10 @@ impl ComposeFilePreset {
 		for service_data in merged_preset.config.services.values_mut() {
 			match service_data {
 				ServicePresetRef::PresetId(id) => {
-					let service_preset = config.get_service_preset(id)?;
+					let mut service_preset = config.get_service_preset(id)?;
+
+					// Only needed for merging, it should not end up in the output
+					service_preset.extends_presets.clear();
 
 					*service_data = ServicePresetRef::Preset(service_preset.into());
 				}
@@ -87,6 +96,7 @@ impl ComposeFilePreset {
 						let mut data = std::mem::take(preset);
 
 						*data = data.merge_presets("__inlined", &config.service_presets)?;
+						data.extends_presets.clear();
 
 						*preset = data;
 					}
@@ -143,4 +153,8 @@ impl ExtensiblePreset for DockerServicePreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
@@ -1,4 +1,8 @@
-use std::{io, path::PathBuf};
+use std::{
+	fmt::Display,
+	io,
+	path::{Path, PathBuf},
+};
 
 use thiserror::Error;
 
@@ -20,31 +24,384 @@ pub enum AppError {
 	PathCanonicalization { path: PathBuf, source: io::Error },
 
 	// Invalid values
-	#[error("{kind:?} preset `{name}` not found")]
-	PresetNotFound { kind: PresetKind, name: String },
+	#[error("{kind:?} preset `{name}` not found{}", did_you_mean(.suggestion.as_deref()))]
+	PresetNotFound {
+		kind: PresetKind,
+		name: String,
+		/// The closest existing id, if there is one.
+		suggestion: Option<String>,
+	},
 
-	#[error("Failed to parse the template `{template}`: {source}")]
+	#[error("Failed to parse the template `{template}`: {}", tera_error_chain(.source))]
 	TemplateParsing {
 		template: String,
 		source: ::tera::Error,
 	},
 
-	#[error("Failed to render the template `{template}`: {source}")]
+	#[error("Failed to render the template `{template}`: {}", tera_error_chain(.source))]
 	TemplateRendering {
 		template: String,
 		source: ::tera::Error,
 	},
 
 	#[error("{0}")]
-	CircularDependency(String),
+	CircularDependency(DependencyCycle),
+
+	#[error("{source}\nThe loaded config files are (from lowest to highest priority):\n{}", list_files(.files))]
+	NotFoundInSources {
+		source: Box<Self>,
+		files: Vec<PathBuf>,
+	},
+
+	#[error("The config file `{}` targets sketch v{version}, but the installed version is v{}. Please update sketch to use this config", file.display(), env!("CARGO_PKG_VERSION"))]
+	NewerConfigVersion { file: PathBuf, version: String },
+
+	#[error("The environment variable `{name}` used in `{key_path}` in the config file `{}` is not set{}", file.display(), format_location(file, .location.as_ref()))]
+	MissingEnvVar {
+		name: String,
+		key_path: String,
+		file: PathBuf,
+		/// The position of the expression in the file, when it is known.
+		location: Option<SourceLocation>,
+	},
+
+	#[error("Found {} error(s) in the config:\n{}", .0.len(), list_issues(.0))]
+	InvalidConfig(Vec<ConfigIssue>),
+
+	#[error("Found {} invalid variable(s):\n{}", .0.len(), list_issues(.0))]
+	InvalidVars(Vec<VarIssue>),
+
+	#[error("Found {} file(s) that do not match the presets:\n{}", .0.len(), list_issues(.0))]
+	OutOfDateFiles(Vec<OutOfDateFile>),
 
 	// Serde errors
 	#[error("Error while serializing the contents of `{file:?}`: {error}")]
 	SerializationError { file: PathBuf, error: String },
 
-	#[error("Error while deserializing the contents of `{file:?}`: {error}")]
-	DeserializationError { file: PathBuf, error: String },
+	#[error("Error while deserializing the contents of `{file:?}`: {error}{}", format_location(file, .location.as_ref()))]
+	DeserializationError {
+		file: PathBuf,
+		error: String,
+		/// The position of the error in the file, when it is known.
+		location: Option<SourceLocation>,
+	},
 
 	#[error("{0:?}")]
 	Other(#[from] anyhow::Error),
 }
+
+impl AppError {
+	/// Creates a [`AppError::PresetNotFound`], suggesting the closest id among the existing ones.
+	pub(crate) fn preset_not_found<'a>(
+		kind: PresetKind,
+		name: &str,
+		existing_ids: impl IntoIterator<Item = &'a String>,
+	) -> Self {
+		Self::PresetNotFound {
+			kind,
+			name: name.to_string(),
+			suggestion: closest_match(name, existing_ids).cloned(),
+		}
+	}
+
+	/// The name of the variant, which is used in the json output.
+	#[must_use]
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Self::Other(_) => "Other",
+			Self::DirCreation { .. } => "DirCreation",
+			Self::WriteError { .. } => "WriteError",
+			Self::ReadError { .. } => "ReadError",
+			Self::PathCanonicalization { .. } => "PathCanonicalization",
+			Self::PresetNotFound { .. } => "PresetNotFound",
+			Self::TemplateParsing { .. } => "TemplateParsing",
+			Self::TemplateRendering { .. } => "TemplateRendering",
+			Self::CircularDependency(_) => "CircularDependency",
+			Self::NotFoundInSources { source, .. } => source.kind(),
+			Self::NewerConfigVersion { .. } => "NewerConfigVersion",
+			Self::MissingEnvVar { .. } => "MissingEnvVar",
+			Self::InvalidConfig(_) => "InvalidConfig",
+			Self::InvalidVars(_) => "InvalidVars",
+			Self::OutOfDateFiles(_) => "OutOfDateFiles",
+			Self::SerializationError { .. } => "SerializationError",
+			Self::DeserializationError { .. } => "DeserializationError",
+		}
+	}
+
+	/// A stable code that identifies the kind of the error.
+	#[must_use]
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::Other(_) => "E0000",
+			Self::DirCreation { .. } => "E0001",
+			Self::WriteError { .. } => "E0002",
+			Self::ReadError { .. } => "E0003",
+			Self::PathCanonicalization { .. } => "E0004",
+			Self::PresetNotFound { .. } => "E0005",
+			Self::TemplateParsing { .. } => "E0006",
+			Self::TemplateRendering { .. } => "E0007",
+			Self::CircularDependency(_) => "E0008",
+			// It only adds context to the original error
+			Self::NotFoundInSources { source, .. } => source.code(),
+			Self::NewerConfigVersion { .. } => "E0009",
+			Self::MissingEnvVar { .. } => "E0010",
+			Self::InvalidConfig(_) => "E0011",
+			Self::InvalidVars(_) => "E0012",
+			Self::OutOfDateFiles(_) => "E0013",
+			Self::SerializationError { .. } => "E0014",
+			Self::DeserializationError { .. } => "E0015",
+		}
+	}
+}
+
+/// A position inside of a file, along with the line that contains it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+	/// The line number, starting from 1.
+	pub line: usize,
+	/// The column number (in characters), starting from 1.
+	pub column: usize,
+	/// The content of the line.
+	pub source_line: String,
+}
+
+impl SourceLocation {
+	/// Creates a location from a line and a column, starting from 1.
+	pub(crate) fn from_line_col(content: &str, line: usize, column: usize) -> Option<Self> {
+		let source_line = content.lines().nth(line.checked_sub(1)?)?;
+
+		Some(Self {
+			line,
+			column: column.max(1),
+			source_line: source_line.to_string(),
+		})
+	}
+
+	/// Creates a location from a byte offset.
+	pub(crate) fn from_offset(content: &str, offset: usize) -> Option<Self> {
+		let before = content.get(..offset)?;
+
+		let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+		Self::from_line_col(
+			content,
+			before.matches('\n').count() + 1,
+			before[line_start..].chars().count() + 1,
+		)
+	}
+}
+
+fn format_location(file: &Path, location: Option<&SourceLocation>) -> String {
+	let Some(location) = location else {
+		return String::new();
+	};
+
+	let line_number = location.line.to_string();
+	let padding = " ".repeat(line_number.len());
+
+	// Tabs are replaced so that the caret is aligned with the content
+	let source_line = location.source_line.replace('\t', " ");
+
+	format!(
+		"\n{padding}--> {}:{}:{}\n{padding} |\n{line_number} | {source_line}\n{padding} | {}^",
+		file.display(),
+		location.line,
+		location.column,
+		" ".repeat(location.column - 1)
+	)
+}
+
+fn did_you_mean(suggestion: Option<&str>) -> String {
+	suggestion.map_or_else(String::new, |suggestion| {
+		format!(". Did you mean `{suggestion}`?")
+	})
+}
+
+/// Returns the candidate that is closest to `name`, if it is close enough to be a likely typo.
+pub(crate) fn closest_match<'a>(
+	name: &str,
+	candidates: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a String> {
+	let max_distance = (name.chars().count() / 3).max(1);
+
+	candidates
+		.into_iter()
+		.map(|candidate| (edit_distance(name, candidate), candidate))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+
+	let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+	for (i, a_char) in a.chars().enumerate() {
+		let mut current_row = vec![i + 1];
+
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous_row[j] + usize::from(a_char != *b_char);
+			let insertion = current_row[j] + 1;
+			let deletion = previous_row[j + 1] + 1;
+
+			current_row.push(substitution.min(insertion).min(deletion));
+		}
+
+		previous_row = current_row;
+	}
+
+	previous_row[b.len()]
+}
+
+/// A chain of presets or config files that depend on each other in a loop.
+#[derive(Debug)]
+pub struct DependencyCycle {
+	/// The kind of the presets in the chain, or `None` if the chain is made of config files.
+	pub kind: Option<PresetKind>,
+	/// The processed chain, ending with the item that closes the loop.
+	pub chain: Vec<String>,
+	/// The config files where each preset in the chain is defined, when known.
+	pub sources: Vec<Option<PathBuf>>,
+}
+
+impl DependencyCycle {
+	pub(crate) const fn new(kind: Option<PresetKind>, chain: Vec<String>) -> Self {
+		Self {
+			kind,
+			chain,
+			sources: Vec::new(),
+		}
+	}
+}
+
+impl Display for DependencyCycle {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let last = self
+			.chain
+			.last()
+			.map(String::as_str)
+			.unwrap_or_default();
+
+		let chain: Vec<String> = self
+			.chain
+			.iter()
+			.enumerate()
+			.map(|(i, item)| match self.sources.get(i) {
+				Some(Some(source)) => format!("{item} (`{}`)", source.display()),
+				_ => item.clone(),
+			})
+			.collect();
+
+		match self.kind {
+			Some(kind) => write!(
+				f,
+				"Found circular {kind:?} dependency for preset with id '{last}'. The full processed chain is: {}",
+				chain.join(" -> ")
+			),
+			None => write!(
+				f,
+				"Found circular dependency to the config file {last}. The full processed path is: {}",
+				chain.join(" -> ")
+			),
+		}
+	}
+}
+
+/// An error found while validating a [`Config`](crate::Config), along with the location of the item that caused it.
+#[derive(Debug)]
+pub struct ConfigIssue {
+	/// The path to the item that contains the error (i.e. `rust.crate_presets.my_crate`).
+	pub location: String,
+	pub error: AppError,
+}
+
+impl Display for ConfigIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "`{}`: {}", self.location, self.error)
+	}
+}
+
+/// A variable that does not match its declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarIssue {
+	/// The name of the variable.
+	pub var: String,
+	/// The id of the templating preset where the variable was declared, if it was not declared globally.
+	pub preset: Option<String>,
+	pub problem: String,
+}
+
+impl Display for VarIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.preset {
+			Some(preset) => write!(
+				f,
+				"`{}` (templating preset `{preset}`): {}",
+				self.var, self.problem
+			),
+			None => write!(f, "`{}`: {}", self.var, self.problem),
+		}
+	}
+}
+
+/// A generated file that does not match the output of its presets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfDateFile {
+	/// The path to the file, relative to the root of the project (or to the cwd, with the `--check` flag).
+	pub path: PathBuf,
+	pub kind: OutOfDateKind,
+}
+
+/// The ways in which a file can differ from the output of its presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfDateKind {
+	/// The file is generated by the presets, but it does not exist.
+	Missing,
+	/// The contents of the file are different from the generated ones.
+	Different,
+	/// The file was generated before, but it is no longer generated by the presets.
+	Extra,
+}
+
+impl Display for OutOfDateFile {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let problem = match self.kind {
+			OutOfDateKind::Missing => "is missing",
+			OutOfDateKind::Different => "is different from the generated version",
+			OutOfDateKind::Extra => "is no longer generated by the presets",
+		};
+
+		write!(f, "`{}` {problem}", self.path.display())
+	}
+}
+
+fn list_issues<T: Display>(issues: &[T]) -> String {
+	issues
+		.iter()
+		.map(|issue| format!("  - {issue}"))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Tera only shows the details of an error (like the name of an undefined variable) in its sources.
+fn tera_error_chain(error: &::tera::Error) -> String {
+	let mut messages = vec![error.to_string()];
+	let mut source = std::error::Error::source(error);
+
+	while let Some(inner) = source {
+		messages.push(inner.to_string());
+		source = inner.source();
+	}
+
+	messages.join(": ")
+}
+
+fn list_files(files: &[PathBuf]) -> String {
+	files
+		.iter()
+		.map(|file| format!("  - `{}`", file.display()))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
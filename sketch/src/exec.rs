@@ -42,7 +42,25 @@ impl Config {
 	) -> Result<(), AppError> {
 		let mut tera = self.initialize_tera()?;
 
-		let mut global_context = create_context(&self.vars)?;
+		let mut global_vars = self.vars.clone();
+		insert_var_defaults(&self.var_declarations, &mut global_vars, None);
+
+		// Every command is checked before executing anything
+		let mut issues: Vec<VarIssue> = Vec::new();
+
+		for cmd in &commands {
+			let mut cmd_vars = global_vars.clone();
+			cmd_vars.extend(cmd.context.clone());
+			cmd_vars.extend(cli_vars.clone());
+
+			check_vars(&self.var_declarations, &cmd_vars, None, &mut issues);
+		}
+
+		if !issues.is_empty() {
+			return Err(AppError::InvalidVars(issues));
+		}
+
+		let mut global_context = create_context(&global_vars)?;
 		global_context.extend(get_default_context());
 
 		let mut template_context = TemplateContext::new(&global_context, cli_vars);
@@ -72,9 +90,9 @@ impl Config {
 					source: e,
 				})?;
 
-			if print_cmd {
-				println!("Rendered command:");
-				println!("{rendered_command}");
+			// In the json output, the command is included in the events
+			if print_cmd && !is_json_output() {
+				emit_message(format!("Rendered command:\n{rendered_command}"));
 			}
 
 			let shell = shell.unwrap_or_else(|| default_shell());
@@ -90,19 +108,101 @@ impl Config {
 	}
 }
 
+/// A shell command that is launched during the generation of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCommand {
+	/// The program to execute.
+	pub program: String,
+	/// The arguments for the program.
+	pub args: Vec<String>,
+	/// The working directory of the command.
+	pub cwd: PathBuf,
+}
+
+impl PlannedCommand {
+	pub(crate) fn new(program: &str, args: &[&str], cwd: &Path) -> Self {
+		Self {
+			program: program.to_string(),
+			args: args
+				.iter()
+				.map(|arg| (*arg).to_string())
+				.collect(),
+			cwd: cwd.to_path_buf(),
+		}
+	}
+
+	/// Executes the command.
+	pub fn run(&self) -> AppResult {
+		let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+
+		launch_command(&self.program, &args, &self.cwd, None)
+	}
+}
+
+impl Display for PlannedCommand {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let command = std::iter::once(&self.program)
+			.chain(&self.args)
+			.map(|arg| {
+				if arg.contains(char::is_whitespace) {
+					format!("'{arg}'")
+				} else {
+					arg.clone()
+				}
+			})
+			.collect::<Vec<_>>()
+			.join(" ");
+
+		f.write_str(&command)
+	}
+}
+
 pub(crate) fn launch_command(
 	program: &str,
 	commands: &[&str],
 	cwd: &Path,
 	custom_error_message: Option<&str>,
 ) -> Result<(), AppError> {
-	let output = Command::new(program)
-		.args(commands)
-		.current_dir(cwd)
-		.stdout(Stdio::inherit())
-		.stderr(Stdio::inherit())
-		.output()
-		.with_context(|| format!("Failed to execute shell command '{}'", commands.join(" ")))?;
+	let planned_command = PlannedCommand::new(program, commands, cwd);
+	let command_str = planned_command.to_string();
+
+	if dry_run_command(&planned_command) {
+		emit(OutputEvent::CommandPlanned {
+			command: command_str,
+			cwd: virtual_path(cwd),
+		});
+
+		return Ok(());
+	}
+
+	emit(OutputEvent::CommandStarted {
+		command: command_str.clone(),
+		cwd: virtual_path(cwd),
+	});
+
+	// The stdout is reserved for the events in the json output
+	let stdout = if is_json_output() {
+		Stdio::from(std::io::stderr())
+	} else {
+		Stdio::inherit()
+	};
+
+	let output = journal_new_entries(cwd, || {
+		Command::new(program)
+			.args(commands)
+			.current_dir(cwd)
+			.stdout(stdout)
+			.stderr(Stdio::inherit())
+			.output()
+	})
+	.with_context(|| format!("Failed to execute shell command '{}'", commands.join(" ")))?;
+
+	emit(OutputEvent::CommandFinished {
+		command: command_str,
+		cwd: virtual_path(cwd),
+		exit_code: output.status.code(),
+		success: output.status.success(),
+	});
 
 	if output.status.success() {
 		Ok(())
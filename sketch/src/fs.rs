@@ -1,7 +1,19 @@
+use std::ops::Range;
+
 use serde::de::DeserializeOwned;
 
 use crate::*;
 
+mod dry_run;
+pub(crate) use dry_run::*;
+
+mod overwrite;
+pub(crate) use overwrite::*;
+mod review;
+pub(crate) use review::*;
+mod transaction;
+pub(crate) use transaction::*;
+
 pub(crate) fn create_dirs_from_stripped_glob(glob: &Path) -> Result<(), AppError> {
 	let glob_str = glob.to_string_lossy();
 
@@ -68,21 +80,12 @@ pub(crate) fn serialize_toml<T: Serialize>(
 	path: &Path,
 	overwrite: bool,
 ) -> Result<(), AppError> {
-	let mut output_file = open_file_if_overwriting(overwrite, path)?;
-
 	let content = toml::to_string(item).map_err(|e| AppError::SerializationError {
 		file: path.to_path_buf(),
 		error: e.to_string(),
 	})?;
 
-	output_file
-		.write_all(content.as_bytes())
-		.map_err(|e| AppError::WriteError {
-			path: path.to_path_buf(),
-			source: e,
-		})?;
-
-	Ok(())
+	write_file(path, &content, overwrite)
 }
 
 pub(crate) fn serialize_yaml<T: Serialize>(
@@ -90,12 +93,12 @@ pub(crate) fn serialize_yaml<T: Serialize>(
 	path: &Path,
 	overwrite: bool,
 ) -> Result<(), AppError> {
-	let output_file = open_file_if_overwriting(overwrite, path)?;
-
-	serde_yaml_ng::to_writer(output_file, item).map_err(|e| AppError::SerializationError {
+	let content = serde_yaml_ng::to_string(item).map_err(|e| AppError::SerializationError {
 		file: path.to_path_buf(),
 		error: e.to_string(),
-	})
+	})?;
+
+	write_file(path, &content, overwrite)
 }
 
 pub(crate) fn serialize_json<T: Serialize>(
@@ -103,62 +106,179 @@ pub(crate) fn serialize_json<T: Serialize>(
 	path: &Path,
 	overwrite: bool,
 ) -> Result<(), AppError> {
-	let output_file = open_file_if_overwriting(overwrite, path)?;
-
-	serde_json::to_writer_pretty(output_file, item).map_err(|e| AppError::SerializationError {
+	let content = serde_json::to_string_pretty(item).map_err(|e| AppError::SerializationError {
 		file: path.to_path_buf(),
 		error: e.to_string(),
-	})
+	})?;
+
+	write_file(path, &content, overwrite)
 }
 
 pub(crate) fn deserialize_toml<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
-	let contents = read_to_string(path).map_err(|e| AppError::ReadError {
-		path: path.to_path_buf(),
-		source: e,
-	})?;
-
-	toml::from_str(&contents).map_err(|e| AppError::DeserializationError {
-		file: path.to_path_buf(),
-		error: e.to_string(),
-	})
+	parse_toml(path, &read_file_to_string(path)?)
 }
 
 pub(crate) fn deserialize_json<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
-	let file = read_file(path)?;
+	parse_json(path, &read_file_to_string(path)?)
+}
 
-	serde_json::from_reader(file).map_err(|e| AppError::DeserializationError {
-		file: path.to_path_buf(),
-		error: e.to_string(),
+pub(crate) fn deserialize_yaml<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
+	parse_yaml(path, &read_file_to_string(path)?)
+}
+
+/// Deserializes the contents of a toml file that has already been read.
+pub(crate) fn parse_toml<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, AppError> {
+	toml::from_str(contents)
+		.map_err(|e| toml_deserialization_error(path, contents, e.message(), e.span()))
+}
+
+/// Deserializes the contents of a json file that has already been read.
+pub(crate) fn parse_json<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, AppError> {
+	serde_json::from_str(contents).map_err(|e| {
+		let location = SourceLocation::from_line_col(contents, e.line(), e.column());
+
+		AppError::DeserializationError {
+			file: path.to_path_buf(),
+			error: strip_position(e.to_string(), location.as_ref()),
+			location,
+		}
 	})
 }
 
-pub(crate) fn deserialize_yaml<T: DeserializeOwned>(path: &Path) -> Result<T, AppError> {
-	let file = read_file(path)?;
+/// Deserializes the contents of a yaml file that has already been read.
+pub(crate) fn parse_yaml<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, AppError> {
+	serde_yaml_ng::from_str(contents).map_err(|e| {
+		let location = e.location().and_then(|location| {
+			SourceLocation::from_line_col(contents, location.line(), location.column())
+		});
+
+		AppError::DeserializationError {
+			file: path.to_path_buf(),
+			error: strip_position(e.to_string(), location.as_ref()),
+			location,
+		}
+	})
+}
 
-	serde_yaml_ng::from_reader(file).map_err(|e| AppError::DeserializationError {
+/// Creates a deserialization error from the message and the span of a toml error, which is used for both `toml` and `toml_edit`.
+pub(crate) fn toml_deserialization_error(
+	path: &Path,
+	contents: &str,
+	message: &str,
+	span: Option<Range<usize>>,
+) -> AppError {
+	AppError::DeserializationError {
 		file: path.to_path_buf(),
-		error: e.to_string(),
-	})
+		error: message.trim_end().to_string(),
+		location: span.and_then(|span| SourceLocation::from_offset(contents, span.start)),
+	}
+}
+
+/// Removes the position that the json and yaml parsers append to their messages, since it is shown along with the source line.
+fn strip_position(message: String, location: Option<&SourceLocation>) -> String {
+	let Some(location) = location else {
+		return message;
+	};
+
+	let suffix = format!(" at line {} column {}", location.line, location.column);
+
+	match message.strip_suffix(&suffix) {
+		Some(stripped) => stripped.to_string(),
+		None => message,
+	}
 }
 
-pub(crate) fn read_file(path: &Path) -> Result<File, AppError> {
-	File::open(path).map_err(|e| AppError::ReadError {
+/// Reads a file, including the files written in dry-run mode.
+pub(crate) fn read_bytes(path: &Path) -> Result<Vec<u8>, AppError> {
+	if let Some(content) = dry_run_read(path) {
+		return Ok(content);
+	}
+
+	std::fs::read(path).map_err(|e| AppError::ReadError {
 		path: path.to_path_buf(),
 		source: e,
 	})
 }
 
+/// Reads a file as a string, including the files written in dry-run mode.
+pub(crate) fn read_file_to_string(path: &Path) -> Result<String, AppError> {
+	let content = read_bytes(path)?;
+
+	String::from_utf8(content).map_err(|e| AppError::ReadError {
+		path: path.to_path_buf(),
+		source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+	})
+}
+
+/// Whether a file exists, including the files written in dry-run mode.
+pub(crate) fn file_exists(path: &Path) -> bool {
+	dry_run_contains_file(path) || path.is_file()
+}
+
+/// Whether a file or directory exists, including the ones created in dry-run mode.
+pub(crate) fn path_exists(path: &Path) -> bool {
+	dry_run_contains(path) || path.exists()
+}
+
 pub(crate) fn write_file(path: &Path, content: &str, overwrite: bool) -> Result<(), AppError> {
+	write_bytes(path, content.as_bytes(), overwrite)
+}
+
+pub(crate) fn write_bytes(path: &Path, content: &[u8], overwrite: bool) -> Result<(), AppError> {
+	try_write_bytes(path, content, overwrite).map(|_| ())
+}
+
+/// Writes a file like [`write_bytes`], and returns `false` if it was not written because of the overwrite policy.
+pub(crate) fn try_write_bytes(
+	path: &Path,
+	content: &[u8],
+	overwrite: bool,
+) -> Result<bool, AppError> {
+	let exists = file_exists(path);
+
+	if exists {
+		if !overwrite {
+			return Err(already_exists_error(path));
+		}
+
+		if !apply_overwrite_policy(path, content)? {
+			return Ok(false);
+		}
+	}
+
+	write_raw(path, content, overwrite)?;
+	track_written_file(path, content);
+
+	let path = virtual_path(path);
+	let preset = current_preset();
+
+	emit(if exists {
+		OutputEvent::FileOverwritten { path, preset }
+	} else {
+		OutputEvent::FileCreated { path, preset }
+	});
+
+	Ok(true)
+}
+
+/// Writes a file without applying the overwrite policies.
+fn write_raw(path: &Path, content: &[u8], overwrite: bool) -> Result<(), AppError> {
+	if let Some(result) = dry_run_write(path, content, overwrite) {
+		return result;
+	}
+
+	journal_write(path);
+
 	let mut file = open_file_if_overwriting(overwrite, path)?;
 
-	file.write_all(content.as_bytes())
+	file.write_all(content)
 		.map_err(|e| AppError::WriteError {
 			path: path.to_path_buf(),
 			source: e,
 		})
 }
 
-pub(crate) fn open_file_if_overwriting(overwrite: bool, path: &Path) -> Result<File, AppError> {
+fn open_file_if_overwriting(overwrite: bool, path: &Path) -> Result<File, AppError> {
 	if overwrite {
 		File::create(path).map_err(|e| AppError::WriteError {
 			path: path.to_path_buf(),
@@ -166,11 +286,7 @@ pub(crate) fn open_file_if_overwriting(overwrite: bool, path: &Path) -> Result<F
 		})
 	} else {
 		File::create_new(path).map_err(|e| match e.kind() {
-			std::io::ErrorKind::AlreadyExists => anyhow!(
-				"The file `{}` already exists. Set `no_overwrite` to false to overwrite existing files",
-				path.display()
-			)
-			.into(),
+			std::io::ErrorKind::AlreadyExists => already_exists_error(path),
 			_ => AppError::WriteError {
 				path: path.to_path_buf(),
 				source: e,
@@ -179,6 +295,14 @@ pub(crate) fn open_file_if_overwriting(overwrite: bool, path: &Path) -> Result<F
 	}
 }
 
+fn already_exists_error(path: &Path) -> AppError {
+	anyhow!(
+		"The file `{}` already exists. Unset `no_overwrite` or change the overwrite policy to overwrite existing files",
+		path.display()
+	)
+	.into()
+}
+
 pub(crate) fn create_parent_dirs(path: &Path) -> Result<(), AppError> {
 	let dirname = get_parent_dir(path)?;
 
@@ -186,16 +310,42 @@ pub(crate) fn create_parent_dirs(path: &Path) -> Result<(), AppError> {
 }
 
 pub(crate) fn create_all_dirs(path: &Path) -> Result<(), AppError> {
+	if dry_run_create_dirs(path) {
+		return Ok(());
+	}
+
+	journal_create_dirs(path);
+
 	Ok(create_dir_all(path)
 		.with_context(|| format!("Could not create the parent dirs for `{}`", path.display()))?)
 }
 
+/// Removes a directory and its contents.
+pub(crate) fn remove_all(path: &Path) -> Result<(), AppError> {
+	if dry_run_remove_dir(path) {
+		return Ok(());
+	}
+
+	journal_remove_dir(path);
+
+	remove_dir_all(path).map_err(|e| AppError::WriteError {
+		path: path.to_path_buf(),
+		source: e,
+	})
+}
+
 pub(crate) fn get_abs_path(path: &Path) -> Result<PathBuf, AppError> {
-	path.canonicalize()
-		.map_err(|e| AppError::PathCanonicalization {
-			path: path.into(),
-			source: e,
-		})
+	path.canonicalize().or_else(|e| {
+		// Paths created in dry-run mode do not exist on disk
+		if dry_run_contains(path) {
+			Ok(virtual_path(path))
+		} else {
+			Err(AppError::PathCanonicalization {
+				path: path.into(),
+				source: e,
+			})
+		}
+	})
 }
 
 pub(crate) fn get_parent_dir(path: &Path) -> Result<&Path, AppError> {
@@ -209,19 +359,9 @@ pub(crate) fn get_cwd() -> PathBuf {
 }
 
 pub(crate) fn get_relative_path(base: &Path, target: &Path) -> Result<PathBuf, AppError> {
-	let canonical_base = base
-		.canonicalize()
-		.map_err(|e| AppError::PathCanonicalization {
-			path: base.to_path_buf(),
-			source: e,
-		})?;
+	let canonical_base = get_abs_path(base)?;
 
-	let canonical_target = target
-		.canonicalize()
-		.map_err(|e| AppError::PathCanonicalization {
-			path: target.to_path_buf(),
-			source: e,
-		})?;
+	let canonical_target = get_abs_path(target)?;
 
 	let base_components: Vec<_> = canonical_base.components().collect();
 	let target_components: Vec<_> = canonical_target.components().collect();
@@ -0,0 +1,247 @@
+use std::{
+	future::Future,
+	sync::{Arc, Mutex},
+};
+
+use super::already_exists_error;
+use crate::{exec::PlannedCommand, *};
+
+tokio::task_local! {
+	static DRY_RUN: Arc<Mutex<DryRun>>;
+}
+
+/// The operations recorded while running a command in dry-run mode.
+///
+/// The written files are kept in memory, so that the later steps of a command can read them as if they had been written to disk.
+#[derive(Debug, Default)]
+pub(crate) struct DryRun {
+	files: IndexMap<PathBuf, Vec<u8>>,
+	dirs: IndexSet<PathBuf>,
+	operations: Vec<FsOperation>,
+}
+
+/// An operation that would be performed outside of dry-run mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FsOperation {
+	CreateDir(PathBuf),
+	CreateFile(PathBuf),
+	OverwriteFile(PathBuf),
+	RemoveDir(PathBuf),
+	SkipFile(PathBuf),
+	RunCommand(PlannedCommand),
+}
+
+impl Display for FsOperation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::CreateDir(path) => write!(f, "Would create the directory `{}`", path.display()),
+			Self::CreateFile(path) => write!(f, "Would create `{}`", path.display()),
+			Self::OverwriteFile(path) => write!(f, "Would overwrite `{}`", path.display()),
+			Self::RemoveDir(path) => write!(f, "Would remove the directory `{}`", path.display()),
+			Self::SkipFile(path) => {
+				write!(
+					f,
+					"Would skip `{}`, since it already exists",
+					path.display()
+				)
+			}
+			Self::RunCommand(command) => {
+				write!(f, "Would run `{command}` in `{}`", command.cwd.display())
+			}
+		}
+	}
+}
+
+impl DryRun {
+	pub(crate) fn operations(&self) -> &[FsOperation] {
+		&self.operations
+	}
+
+	pub(crate) fn commands(&self) -> impl Iterator<Item = &PlannedCommand> {
+		self.operations
+			.iter()
+			.filter_map(|operation| match operation {
+				FsOperation::RunCommand(command) => Some(command),
+				_ => None,
+			})
+	}
+
+	/// The files that would be skipped because of their overwrite policy.
+	pub(crate) fn skipped_files(&self) -> impl Iterator<Item = &Path> {
+		self.operations
+			.iter()
+			.filter_map(|operation| match operation {
+				FsOperation::SkipFile(path) => Some(path.as_path()),
+				_ => None,
+			})
+	}
+
+	/// The contents of the written files and the created directories, by their absolute path.
+	pub(crate) fn into_parts(self) -> (IndexMap<PathBuf, Vec<u8>>, IndexSet<PathBuf>) {
+		(self.files, self.dirs)
+	}
+
+	fn write(&mut self, path: &Path, content: &[u8], overwrite: bool) -> AppResult {
+		let key = virtual_path(path);
+
+		let exists = self.files.contains_key(&key) || path.is_file();
+
+		if exists && !overwrite {
+			return Err(already_exists_error(path));
+		}
+
+		if !self.files.contains_key(&key) {
+			self.operations.push(if exists {
+				FsOperation::OverwriteFile(path.to_path_buf())
+			} else {
+				FsOperation::CreateFile(path.to_path_buf())
+			});
+		}
+
+		self.files.insert(key, content.to_vec());
+
+		Ok(())
+	}
+
+	fn create_dirs(&mut self, path: &Path) {
+		let key = virtual_path(path);
+
+		if path.is_dir() || self.dirs.contains(&key) {
+			return;
+		}
+
+		self.operations
+			.push(FsOperation::CreateDir(path.to_path_buf()));
+
+		for ancestor in key.ancestors() {
+			if ancestor.is_dir() {
+				break;
+			}
+
+			self.dirs.insert(ancestor.to_path_buf());
+		}
+	}
+
+	fn remove_dir(&mut self, path: &Path) {
+		let key = virtual_path(path);
+
+		self.files
+			.retain(|file, _| !file.starts_with(&key));
+		self.dirs.retain(|dir| !dir.starts_with(&key));
+
+		self.operations
+			.push(FsOperation::RemoveDir(path.to_path_buf()));
+	}
+
+	fn contains_file(&self, path: &Path) -> bool {
+		self.files.contains_key(&virtual_path(path))
+	}
+
+	fn contains(&self, path: &Path) -> bool {
+		let key = virtual_path(path);
+
+		self.files.contains_key(&key) || self.dirs.contains(&key)
+	}
+}
+
+/// Runs a future in dry-run mode. The write operations performed by the helpers in this module (and the shell commands launched by `launch_command`) are recorded instead of being performed.
+pub(crate) async fn with_dry_run<F: Future>(future: F) -> (F::Output, DryRun) {
+	let state = Arc::new(Mutex::new(DryRun::default()));
+
+	let output = DRY_RUN.scope(state.clone(), future).await;
+
+	let dry_run = mem::take(&mut *state.lock().expect("Dry run state is poisoned"));
+
+	(output, dry_run)
+}
+
+/// Calls `f` with the dry-run state, if dry-run mode is active.
+fn if_dry_run<T>(f: impl FnOnce(&mut DryRun) -> T) -> Option<T> {
+	DRY_RUN
+		.try_with(|state| f(&mut state.lock().expect("Dry run state is poisoned")))
+		.ok()
+}
+
+pub(super) fn is_dry_run() -> bool {
+	DRY_RUN.try_with(|_| ()).is_ok()
+}
+
+pub(super) fn dry_run_write(path: &Path, content: &[u8], overwrite: bool) -> Option<AppResult> {
+	if_dry_run(|dry_run| dry_run.write(path, content, overwrite))
+}
+
+pub(super) fn dry_run_create_dirs(path: &Path) -> bool {
+	if_dry_run(|dry_run| dry_run.create_dirs(path)).is_some()
+}
+
+pub(super) fn dry_run_remove_dir(path: &Path) -> bool {
+	if_dry_run(|dry_run| dry_run.remove_dir(path)).is_some()
+}
+
+/// Records a file that is skipped because of its overwrite policy.
+pub(super) fn dry_run_skip(path: &Path) -> bool {
+	if_dry_run(|dry_run| {
+		dry_run
+			.operations
+			.push(FsOperation::SkipFile(path.to_path_buf()));
+	})
+	.is_some()
+}
+
+pub(super) fn dry_run_read(path: &Path) -> Option<Vec<u8>> {
+	if_dry_run(|dry_run| dry_run.files.get(&virtual_path(path)).cloned()).flatten()
+}
+
+pub(super) fn dry_run_contains_file(path: &Path) -> bool {
+	if_dry_run(|dry_run| dry_run.contains_file(path)).unwrap_or(false)
+}
+
+pub(super) fn dry_run_contains(path: &Path) -> bool {
+	if_dry_run(|dry_run| dry_run.contains(path)).unwrap_or(false)
+}
+
+/// Records a shell command in dry-run mode. Returns `false` if the command should be executed.
+pub(crate) fn dry_run_command(command: &PlannedCommand) -> bool {
+	if_dry_run(|dry_run| {
+		dry_run
+			.operations
+			.push(FsOperation::RunCommand(command.clone()));
+	})
+	.is_some()
+}
+
+/// Resolves a path that may not exist yet, by canonicalizing its closest existing ancestor.
+pub(crate) fn virtual_path(path: &Path) -> PathBuf {
+	let mut abs_path = PathBuf::new();
+
+	for component in get_cwd().join(path).components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				abs_path.pop();
+			}
+			_ => abs_path.push(component),
+		}
+	}
+
+	let mut missing_components = Vec::new();
+	let mut existing = abs_path.as_path();
+
+	loop {
+		if let Ok(canonical) = existing.canonicalize() {
+			let mut resolved = canonical;
+
+			resolved.extend(missing_components.iter().rev());
+
+			return resolved;
+		}
+
+		match (existing.parent(), existing.file_name()) {
+			(Some(parent), Some(name)) => {
+				missing_components.push(name.to_os_string());
+				existing = parent;
+			}
+			_ => return abs_path,
+		}
+	}
+}
@@ -0,0 +1,175 @@
+use std::future::Future;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use super::{
+	already_exists_error, ask_overwrite, dry_run_skip, review_overwrite, virtual_path, write_raw,
+};
+use crate::*;
+
+tokio::task_local! {
+	static OVERWRITE_POLICIES: OverwritePolicies;
+}
+
+/// The policies that decide what happens to the generated files that already exist.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OverwritePolicies {
+	default: OverwritePolicy,
+	preset: Option<OverwritePolicy>,
+	rules: Vec<(GlobMatcher, OverwritePolicy)>,
+}
+
+impl OverwritePolicies {
+	pub(crate) fn from_config(config: &Config) -> AppResult<Self> {
+		let rules = config
+			.overwrite_rules
+			.iter()
+			.map(|(pattern, policy)| {
+				// Patterns match the end of the path, like in a gitignore file
+				let full_pattern = if pattern.starts_with("**") {
+					pattern.clone()
+				} else {
+					format!("**/{}", pattern.trim_start_matches("./"))
+				};
+
+				let matcher = GlobBuilder::new(&full_pattern)
+					.literal_separator(true)
+					.build()
+					.with_context(|| {
+						format!("Could not parse the glob pattern `{pattern}` in `overwrite_rules`")
+					})?
+					.compile_matcher();
+
+				Ok((matcher, *policy))
+			})
+			.collect::<AppResult<Vec<_>>>()?;
+
+		Ok(Self {
+			default: config.overwrite.unwrap_or_default(),
+			preset: None,
+			rules,
+		})
+	}
+
+	/// Policies that overwrite every file, including the files of the presets that have their own policy.
+	pub(crate) fn overwrite_all() -> Self {
+		let matcher = GlobBuilder::new("**")
+			.build()
+			.expect("`**` is a valid glob")
+			.compile_matcher();
+
+		Self {
+			default: OverwritePolicy::Overwrite,
+			preset: None,
+			rules: vec![(matcher, OverwritePolicy::Overwrite)],
+		}
+	}
+
+	/// The policy for a file. The rules take precedence over the policy of the preset, which takes precedence over the default policy.
+	fn get(&self, path: &Path) -> OverwritePolicy {
+		self.rules
+			.iter()
+			.rev()
+			.find(|(matcher, _)| matcher.is_match(path))
+			.map(|(_, policy)| *policy)
+			.or(self.preset)
+			.unwrap_or(self.default)
+	}
+
+	fn with_preset(&self, policy: Option<OverwritePolicy>) -> Self {
+		let mut policies = self.clone();
+
+		if policy.is_some() {
+			policies.preset = policy;
+		}
+
+		policies
+	}
+}
+
+fn current_policies() -> OverwritePolicies {
+	OVERWRITE_POLICIES
+		.try_with(Clone::clone)
+		.unwrap_or_default()
+}
+
+/// Runs a future where the existing files are handled according to the given policies.
+pub(crate) async fn with_overwrite_policies<F: Future>(
+	policies: OverwritePolicies,
+	future: F,
+) -> F::Output {
+	OVERWRITE_POLICIES.scope(policies, future).await
+}
+
+/// Runs a future where the policy of a preset (if it has one) is used for the files that are not matched by any rule.
+pub(crate) async fn with_preset_policy<F: Future>(
+	policy: Option<OverwritePolicy>,
+	future: F,
+) -> F::Output {
+	OVERWRITE_POLICIES
+		.scope(current_policies().with_preset(policy), future)
+		.await
+}
+
+/// The synchronous version of [`with_preset_policy`].
+pub(crate) fn with_preset_policy_sync<T>(
+	policy: Option<OverwritePolicy>,
+	f: impl FnOnce() -> T,
+) -> T {
+	OVERWRITE_POLICIES.sync_scope(current_policies().with_preset(policy), f)
+}
+
+/// Runs a function where the existing files are always overwritten, which is used for the files managed internally by `sketch`.
+pub(crate) fn without_overwrite_policies<T>(f: impl FnOnce() -> T) -> T {
+	OVERWRITE_POLICIES.sync_scope(OverwritePolicies::default(), f)
+}
+
+/// Applies the overwrite policy for an existing file.
+///
+/// Returns `false` if the new content should not be written.
+pub(super) fn apply_overwrite_policy(path: &Path, new_content: &[u8]) -> AppResult<bool> {
+	let policy = current_policies().get(path);
+
+	match policy {
+		OverwritePolicy::Error => return Err(already_exists_error(path)),
+		OverwritePolicy::Skip => {
+			// In dry-run mode, the skipped files are listed along with the other operations
+			if !dry_run_skip(path) && !is_json_output() {
+				emit_message(format!(
+					"Skipped `{}`, since it already exists",
+					path.display()
+				));
+			}
+
+			emit(OutputEvent::FileSkipped {
+				path: virtual_path(path),
+				preset: current_preset(),
+			});
+
+			return Ok(false);
+		}
+		OverwritePolicy::Ask => {
+			if !ask_overwrite(path, new_content)? {
+				return Ok(false);
+			}
+		}
+		OverwritePolicy::Overwrite | OverwritePolicy::Backup => {
+			if !review_overwrite(path, new_content)? {
+				return Ok(false);
+			}
+		}
+	};
+
+	if policy == OverwritePolicy::Backup {
+		let old_content = read_bytes(path)?;
+
+		if old_content != new_content {
+			let mut backup_path = path.as_os_str().to_owned();
+			backup_path.push(".orig");
+
+			write_raw(Path::new(&backup_path), &old_content, true)?;
+		}
+	}
+
+	Ok(true)
+}
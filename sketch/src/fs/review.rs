@@ -0,0 +1,159 @@
+use std::{
+	future::Future,
+	io::{BufRead, IsTerminal},
+};
+
+use diffy::{DiffOptions, PatchFormatter};
+
+use super::is_dry_run;
+use crate::*;
+
+tokio::task_local! {
+	static REVIEW: ReviewOptions;
+}
+
+/// Defines how the changes to existing files are reviewed before they are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ReviewOptions {
+	/// Prints a diff between the existing file and the new content.
+	pub diff: bool,
+	/// Asks whether the changes to each file should be applied.
+	pub confirm: bool,
+}
+
+/// Runs a future where the changes to the existing files are reviewed according to the given options.
+pub(crate) async fn with_review<F: Future>(options: ReviewOptions, future: F) -> F::Output {
+	REVIEW.scope(options, future).await
+}
+
+/// Runs a function without reviewing the changes to existing files, which is used for the files managed internally by `sketch`.
+pub(crate) fn without_review<T>(f: impl FnOnce() -> T) -> T {
+	REVIEW.sync_scope(ReviewOptions::default(), f)
+}
+
+/// Shows the changes to an existing file and asks for confirmation, if required.
+///
+/// Returns `false` if the new content should not be written.
+pub(super) fn review_overwrite(path: &Path, new_content: &[u8]) -> AppResult<bool> {
+	let Ok(options) = REVIEW.try_with(|options| *options) else {
+		return Ok(true);
+	};
+
+	if !(options.diff || options.confirm) || !file_exists(path) {
+		return Ok(true);
+	}
+
+	show_changes(path, new_content, options.confirm)
+}
+
+/// Shows the changes to an existing file and asks whether they should be applied, regardless of the review options.
+pub(super) fn ask_overwrite(path: &Path, new_content: &[u8]) -> AppResult<bool> {
+	show_changes(path, new_content, true)
+}
+
+fn show_changes(path: &Path, new_content: &[u8], confirm: bool) -> AppResult<bool> {
+	let old_content = read_bytes(path)?;
+
+	if old_content == new_content {
+		return Ok(true);
+	}
+
+	let ask = confirm && !is_dry_run();
+
+	if ask && !std::io::stdin().is_terminal() {
+		return Err(anyhow!(
+			"Cannot ask whether the changes to `{}` should be applied, because the input is not interactive. Use `--diff` instead of `--confirm`, or an overwrite policy other than `ask`",
+			path.display()
+		)
+		.into());
+	}
+
+	// In the json output, stdout only contains the events
+	let (mut writer, color): (Box<dyn Write>, bool) = if is_json_output() {
+		let stderr = std::io::stderr();
+		let color = stderr.is_terminal();
+
+		(Box::new(stderr.lock()), color)
+	} else {
+		let stdout = std::io::stdout();
+		let color = stdout.is_terminal();
+
+		(Box::new(stdout.lock()), color)
+	};
+
+	write_diff(&mut writer, path, &old_content, new_content, color)?;
+
+	if ask {
+		let apply = confirm_overwrite(&mut std::io::stdin().lock(), &mut writer, path)?;
+
+		if !apply {
+			writeln!(writer, "Skipped `{}`", path.display()).map_err(|e| output_error(&e))?;
+		}
+
+		return Ok(apply);
+	}
+
+	Ok(true)
+}
+
+fn output_error(e: &std::io::Error) -> AppError {
+	anyhow!("Failed to write the output: {e}").into()
+}
+
+/// Writes a unified diff between the old and the new content of a file.
+pub(crate) fn write_diff<W: Write>(
+	writer: &mut W,
+	path: &Path,
+	old_content: &[u8],
+	new_content: &[u8],
+	color: bool,
+) -> AppResult {
+	let (Ok(old_content), Ok(new_content)) =
+		(str::from_utf8(old_content), str::from_utf8(new_content))
+	else {
+		return writeln!(writer, "Binary file `{}` would change", path.display())
+			.map_err(|e| output_error(&e));
+	};
+
+	let patch = DiffOptions::new()
+		.set_original_filename(format!("a/{}", path.display()))
+		.set_modified_filename(format!("b/{}", path.display()))
+		.create_patch(old_content, new_content);
+
+	let formatter = if color {
+		PatchFormatter::new().with_color()
+	} else {
+		PatchFormatter::new()
+	};
+
+	write!(writer, "{}", formatter.fmt_patch(&patch)).map_err(|e| output_error(&e))
+}
+
+/// Asks whether the changes to a file should be applied. An empty answer (or a closed input) means no.
+pub(crate) fn confirm_overwrite<R: BufRead, W: Write>(
+	reader: &mut R,
+	writer: &mut W,
+	path: &Path,
+) -> AppResult<bool> {
+	loop {
+		write!(writer, "Apply the changes to `{}`? [y/N]: ", path.display())
+			.map_err(|e| output_error(&e))?;
+		writer.flush().map_err(|e| output_error(&e))?;
+
+		let mut input = String::new();
+
+		let bytes_read = reader
+			.read_line(&mut input)
+			.context("Failed to read the answer")?;
+
+		if bytes_read == 0 {
+			return Ok(false);
+		}
+
+		match input.trim().to_lowercase().as_str() {
+			"y" | "yes" => return Ok(true),
+			"" | "n" | "no" => return Ok(false),
+			_ => writeln!(writer, "Please answer `y` or `n`").map_err(|e| output_error(&e))?,
+		};
+	}
+}
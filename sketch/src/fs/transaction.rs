@@ -0,0 +1,217 @@
+use std::{
+	fs::remove_file,
+	future::Future,
+	sync::{Arc, Mutex},
+};
+
+use walkdir::WalkDir;
+
+use super::virtual_path;
+use crate::*;
+
+tokio::task_local! {
+	static TRANSACTION: Arc<Mutex<Journal>>;
+}
+
+/// The changes made to the filesystem by the current transaction, so that they can be reverted if it fails.
+#[derive(Debug, Default)]
+struct Journal {
+	/// The files and directories that did not exist before the transaction, in creation order, along with whether they are directories.
+	created: IndexMap<PathBuf, bool>,
+	/// The original content of the files that existed before the transaction and were overwritten or removed.
+	replaced: IndexMap<PathBuf, Vec<u8>>,
+}
+
+impl Journal {
+	fn is_created(&self, path: &Path) -> bool {
+		path.ancestors()
+			.any(|ancestor| self.created.contains_key(ancestor))
+	}
+
+	fn record_created(&mut self, path: &Path, is_dir: bool) {
+		if !self.is_created(path) {
+			self.created.insert(path.to_path_buf(), is_dir);
+		}
+	}
+
+	fn record_replaced(&mut self, path: &Path) {
+		if self.is_created(path) || self.replaced.contains_key(path) {
+			return;
+		}
+
+		// A file that cannot be read cannot be restored either, so it is simply left alone
+		if let Ok(content) = std::fs::read(path) {
+			self.replaced.insert(path.to_path_buf(), content);
+		}
+	}
+
+	/// Removes the created files and directories, and restores the original content of the replaced files.
+	fn rollback(self) {
+		for (path, is_dir) in self.created.iter().rev() {
+			// Only the directories created by the transaction itself are removed with their content
+			let result = match path.symlink_metadata() {
+				Ok(metadata) if *is_dir && metadata.is_dir() => remove_dir_all(path),
+				Ok(metadata) if !metadata.is_dir() => remove_file(path),
+				_ => Ok(()),
+			};
+
+			if let Err(e) = result {
+				emit(OutputEvent::Warning {
+					message: format!(
+						"could not remove `{}` while rolling back the changes: {e}",
+						path.display()
+					),
+				});
+			}
+		}
+
+		for (path, content) in self.replaced {
+			let result = path
+				.parent()
+				.map_or(Ok(()), create_dir_all)
+				.and_then(|()| std::fs::write(&path, content));
+
+			if let Err(e) = result {
+				emit(OutputEvent::Warning {
+					message: format!(
+						"could not restore `{}` while rolling back the changes: {e}",
+						path.display()
+					),
+				});
+			}
+		}
+	}
+}
+
+/// Calls `f` with the journal, if a transaction is active.
+fn if_transaction<T>(f: impl FnOnce(&mut Journal) -> T) -> Option<T> {
+	TRANSACTION
+		.try_with(|journal| {
+			f(&mut journal
+				.lock()
+				.expect("Transaction journal is poisoned"))
+		})
+		.ok()
+}
+
+fn is_transaction() -> bool {
+	TRANSACTION.try_with(|_| ()).is_ok()
+}
+
+fn finish<T>(journal: &Arc<Mutex<Journal>>, result: AppResult<T>) -> AppResult<T> {
+	if result.is_err() {
+		mem::take(
+			&mut *journal
+				.lock()
+				.expect("Transaction journal is poisoned"),
+		)
+		.rollback();
+	}
+
+	result
+}
+
+/// Runs a future as a transaction. If it fails, the files and directories that it created with the helpers in this module (or with `launch_command`) are removed, and the files that it overwrote are restored.
+///
+/// If a transaction is already active, the future becomes a part of it.
+pub(crate) async fn with_transaction<T, F: Future<Output = AppResult<T>>>(
+	future: F,
+) -> AppResult<T> {
+	if is_transaction() {
+		return future.await;
+	}
+
+	let journal = Arc::new(Mutex::new(Journal::default()));
+
+	let result = TRANSACTION.scope(journal.clone(), future).await;
+
+	finish(&journal, result)
+}
+
+/// The synchronous version of [`with_transaction`].
+pub(crate) fn with_transaction_sync<T>(f: impl FnOnce() -> AppResult<T>) -> AppResult<T> {
+	if is_transaction() {
+		return f();
+	}
+
+	let journal = Arc::new(Mutex::new(Journal::default()));
+
+	let result = TRANSACTION.sync_scope(journal.clone(), f);
+
+	finish(&journal, result)
+}
+
+/// Records a file that is about to be written.
+pub(super) fn journal_write(path: &Path) {
+	if_transaction(|journal| {
+		let path = virtual_path(path);
+
+		if path.is_file() {
+			journal.record_replaced(&path);
+		} else if path.symlink_metadata().is_err() {
+			journal.record_created(&path, false);
+		}
+	});
+}
+
+/// Records the directories that are about to be created.
+pub(super) fn journal_create_dirs(path: &Path) {
+	if_transaction(|journal| {
+		let path = virtual_path(path);
+
+		// Removing the outermost missing directory also removes the ones inside of it
+		if let Some(outermost) = path
+			.ancestors()
+			.take_while(|ancestor| !ancestor.exists())
+			.last()
+		{
+			journal.record_created(outermost, true);
+		}
+	});
+}
+
+/// Records the files inside of a directory that is about to be removed.
+pub(super) fn journal_remove_dir(path: &Path) {
+	if_transaction(|journal| {
+		for entry in WalkDir::new(virtual_path(path))
+			.into_iter()
+			.filter_map(Result::ok)
+			.filter(|entry| entry.file_type().is_file())
+		{
+			journal.record_replaced(entry.path());
+		}
+	});
+}
+
+/// Runs a shell command (or any other function) and records the files and directories that it created inside of `cwd`.
+pub(crate) fn journal_new_entries<T>(cwd: &Path, f: impl FnOnce() -> T) -> T {
+	if !is_transaction() {
+		return f();
+	}
+
+	let cwd = virtual_path(cwd);
+
+	// The entries are not canonicalized, so that symlinks are removed rather than their targets
+	let list_entries = || -> IndexSet<PathBuf> {
+		std::fs::read_dir(&cwd)
+			.map(|entries| {
+				entries
+					.filter_map(Result::ok)
+					.map(|entry| cwd.join(entry.file_name()))
+					.collect()
+			})
+			.unwrap_or_default()
+	};
+
+	let previous_entries = list_entries();
+
+	let output = f();
+
+	if_transaction(|journal| {
+		for entry in list_entries().difference(&previous_entries) {
+			journal.record_created(entry, entry.is_dir());
+		}
+	});
+
+	output
+}
@@ -0,0 +1,226 @@
+//! A facade for using the generation logic of `sketch` from other tools.
+//!
+//! The files are generated in memory, and the shell commands are collected instead of being executed, so that the caller can decide what to do with them.
+//!
+//! ```no_run
+//! use sketch_it::{Config, generator::Generator, manifest::GenerationCommand};
+//!
+//! # async fn example() -> Result<(), sketch_it::AppError> {
+//! let config = Config::from_file("sketch.yaml")?;
+//!
+//! let output = Generator::new(config)
+//!     .generate(
+//!         GenerationCommand::Repo {
+//!             preset: Some("my-preset".to_string()),
+//!         },
+//!         "my-project",
+//!     )
+//!     .await?;
+//!
+//! for (path, content) in output.files() {
+//!     println!("{}: {} bytes", path.display(), content.len());
+//! }
+//!
+//! output.write()?;
+//! output.run_commands()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{cli::Cli, *};
+
+#[doc(inline)]
+pub use crate::exec::PlannedCommand;
+
+/// Generates files from the presets of a [`Config`] without writing them to disk.
+///
+/// Unlike the cli commands, the generator does not print anything to stdout.
+#[derive(Debug, Clone, Default)]
+pub struct Generator {
+	config: Config,
+	vars: IndexMap<String, Value>,
+}
+
+/// The files generated by a [`Generator`], along with the commands that would be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFiles {
+	root: PathBuf,
+	files: IndexMap<PathBuf, Vec<u8>>,
+	dirs: IndexSet<PathBuf>,
+	skipped: Vec<PathBuf>,
+	commands: Vec<PlannedCommand>,
+	manifest: Option<GenerationManifest>,
+}
+
+impl Generator {
+	/// Creates a new generator that uses the presets and the settings of a config.
+	///
+	/// The missing template variables are never requested interactively, since the generator may not be running in a terminal.
+	#[must_use]
+	pub fn new(mut config: Config) -> Self {
+		config.no_input = true;
+
+		Self {
+			config,
+			vars: IndexMap::new(),
+		}
+	}
+
+	/// Sets the variables for the templates, with the same priority as the variables set via cli.
+	#[must_use]
+	pub fn with_vars(mut self, vars: IndexMap<String, Value>) -> Self {
+		self.vars = vars;
+		self
+	}
+
+	/// The config used by this generator.
+	#[must_use]
+	pub const fn config(&self) -> &Config {
+		&self.config
+	}
+
+	/// Generates a project like the `repo`, `rust crate`, `ts package` and `ts monorepo` commands do. The dependencies of typescript packages are never installed.
+	///
+	/// The existing files in `out_dir` are read as usual (for example, to add a crate to an existing workspace). Unlike in the cli commands, the overwrite policies of the config are not applied (only `no_overwrite` is, and it makes the generation fail if a file already exists), so the existing files are included in the output, and it is up to the caller to decide which ones should be written. The files that are skipped because of the overwrite policy of their preset are listed in [`GeneratedFiles::skipped`].
+	pub async fn generate(
+		&self,
+		command: GenerationCommand,
+		out_dir: impl AsRef<Path>,
+	) -> AppResult<GeneratedFiles> {
+		let out_dir = out_dir.as_ref();
+
+		let command = command.into_cli_command(out_dir.to_path_buf());
+
+		let (result, dry_run) = with_dry_run(async {
+			Box::pin(Cli::execute_command(
+				command,
+				self.config.clone(),
+				self.vars.clone(),
+			))
+			.await?;
+
+			GenerationManifest::from_dir(out_dir)
+		})
+		.await;
+
+		Ok(GeneratedFiles::new(out_dir, result?, dry_run))
+	}
+
+	/// Renders the templates of one or many templating presets in `output_root`.
+	pub async fn render_templates(
+		&self,
+		output_root: impl AsRef<Path>,
+		presets: Vec<TemplatingPresetRef>,
+	) -> AppResult<GeneratedFiles> {
+		let output_root = output_root.as_ref();
+
+		let (result, dry_run) = with_dry_run(async {
+			self.config
+				.render_templating_presets(output_root, presets, &self.vars, None)
+		})
+		.await;
+
+		result?;
+
+		Ok(GeneratedFiles::new(output_root, None, dry_run))
+	}
+}
+
+impl GeneratedFiles {
+	fn new(root: &Path, manifest: Option<GenerationManifest>, dry_run: DryRun) -> Self {
+		let commands = dry_run.commands().cloned().collect();
+		let skipped = dry_run
+			.skipped_files()
+			.map(virtual_path)
+			.collect();
+		let (files, dirs) = dry_run.into_parts();
+
+		let root = virtual_path(root);
+
+		// The manifest is returned separately, so that it does not end up among the generated files
+		let manifest_dir = root.join(MANIFEST_PATH);
+		let manifest_dir = manifest_dir.parent().unwrap_or(&root);
+
+		let is_generated = |path: &PathBuf| manifest.is_none() || !path.starts_with(manifest_dir);
+
+		Self {
+			files: files
+				.into_iter()
+				.filter(|(path, _)| is_generated(path))
+				.collect(),
+			dirs: dirs.into_iter().filter(is_generated).collect(),
+			skipped,
+			root,
+			commands,
+			manifest,
+		}
+	}
+
+	/// The absolute path of the output directory.
+	#[must_use]
+	pub fn root(&self) -> &Path {
+		&self.root
+	}
+
+	/// The contents of the generated files, by their absolute path.
+	///
+	/// Some files may be outside of the output directory, like the `Cargo.toml` of the workspace that a new crate is added to.
+	#[must_use]
+	pub const fn files(&self) -> &IndexMap<PathBuf, Vec<u8>> {
+		&self.files
+	}
+
+	/// The absolute paths of the directories that would be created.
+	#[must_use]
+	pub const fn dirs(&self) -> &IndexSet<PathBuf> {
+		&self.dirs
+	}
+
+	/// Returns the content of a generated file. Relative paths are resolved from the output directory.
+	#[must_use]
+	pub fn get(&self, path: impl AsRef<Path>) -> Option<&[u8]> {
+		self.files
+			.get(&self.root.join(path))
+			.map(Vec::as_slice)
+	}
+
+	/// The absolute paths of the existing files that were not generated again, because of the overwrite policy of their preset.
+	#[must_use]
+	pub fn skipped(&self) -> &[PathBuf] {
+		&self.skipped
+	}
+
+	/// The shell commands that would be executed, in order.
+	#[must_use]
+	pub fn commands(&self) -> &[PlannedCommand] {
+		&self.commands
+	}
+
+	/// The generation manifest, for the commands that create one. It can be stored in the output directory with [`GenerationManifest::write`].
+	#[must_use]
+	pub const fn manifest(&self) -> Option<&GenerationManifest> {
+		self.manifest.as_ref()
+	}
+
+	/// Writes the generated files (and creates the directories) on disk, overwriting the existing files.
+	pub fn write(&self) -> AppResult {
+		for dir in &self.dirs {
+			create_all_dirs(dir)?;
+		}
+
+		for (path, content) in &self.files {
+			create_parent_dirs(path)?;
+
+			write_bytes(path, content, true)?;
+		}
+
+		Ok(())
+	}
+
+	/// Executes the shell commands, in order.
+	pub fn run_commands(&self) -> AppResult {
+		self.commands
+			.iter()
+			.try_for_each(PlannedCommand::run)
+	}
+}
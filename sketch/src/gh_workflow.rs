@@ -88,9 +88,12 @@ impl GithubConfig {
 		let mut preset = if let Some(id) = id {
 			self.workflow_presets
 				.get(id)
-				.ok_or_else(|| AppError::PresetNotFound {
-					kind: PresetKind::GithubWorkflow,
-					name: id.to_string(),
+				.ok_or_else(|| {
+					AppError::preset_not_found(
+						PresetKind::GithubWorkflow,
+						id,
+						self.workflow_presets.keys(),
+					)
 				})?
 				.clone()
 		} else {
@@ -110,9 +113,12 @@ impl GithubConfig {
 	pub fn get_workflow(&self, id: &str) -> AppResult<Workflow> {
 		self.workflow_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::GithubWorkflow,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(
+					PresetKind::GithubWorkflow,
+					id,
+					self.workflow_presets.keys(),
+				)
 			})?
 			.clone()
 			.process_data(id, self)
@@ -140,6 +146,10 @@ impl ExtensiblePreset for GhWorkflowPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 impl GhWorkflowPreset {
@@ -166,9 +176,12 @@ impl GhWorkflowPreset {
 					let mut data = github_config
 						.workflow_job_presets
 						.get(id)
-						.ok_or_else(|| AppError::PresetNotFound {
-							kind: PresetKind::GithubWorkflowJob,
-							name: id.clone(),
+						.ok_or_else(|| {
+							AppError::preset_not_found(
+								PresetKind::GithubWorkflowJob,
+								id,
+								github_config.workflow_job_presets.keys(),
+							)
 						})?
 						.clone();
 
@@ -203,6 +216,10 @@ impl ExtensiblePreset for GhJobPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 impl GithubConfig {
@@ -213,15 +230,21 @@ impl GithubConfig {
 	) -> Result<GhJobPreset, AppError> {
 		let mut merged_preset = preset.merge_presets(id, &self.workflow_job_presets)?;
 
+		// Only needed for merging, it should not end up in the output
+		merged_preset.extends_presets.clear();
+
 		if let Job::Normal(job) = &mut merged_preset.job {
 			for step in job.steps.iter_mut() {
 				if let StepPresetRef::PresetId(id) = step {
 					let data = self
 						.steps_presets
 						.get(id)
-						.ok_or_else(|| AppError::PresetNotFound {
-							kind: PresetKind::GithubWorkflowStep,
-							name: id.clone(),
+						.ok_or_else(|| {
+							AppError::preset_not_found(
+								PresetKind::GithubWorkflowStep,
+								id,
+								self.steps_presets.keys(),
+							)
 						})?
 						.clone();
 
@@ -0,0 +1,105 @@
+use std::{
+	process::Command,
+	time::{Duration, SystemTime},
+};
+
+use crate::*;
+
+/// Clones a git repository in a directory, and checks out a revision if one is given.
+///
+/// Without a revision, only the latest commit of the default branch is cloned.
+pub(crate) fn clone_repo(repo: &str, rev: Option<&str>, dir: &Path) -> AppResult {
+	let mut clone_cmd = Command::new("git");
+	clone_cmd.args(["clone", "--quiet"]);
+
+	if rev.is_none() {
+		clone_cmd.arg("--depth=1");
+	}
+
+	run_git(clone_cmd.arg(repo).arg(dir), &format!("clone `{repo}`"))?;
+
+	if let Some(rev) = rev {
+		run_git(
+			Command::new("git")
+				.arg("-C")
+				.arg(dir)
+				.args(["checkout", "--quiet", rev]),
+			&format!("check out `{rev}` in the clone of `{repo}`"),
+		)?;
+	}
+
+	mark_as_fetched(dir)
+}
+
+/// Fetches the latest commit of a revision (or of the default branch) in an existing clone, and checks it out.
+pub(crate) fn refresh_repo(repo: &str, rev: Option<&str>, dir: &Path) -> AppResult {
+	let mut fetch_cmd = Command::new("git");
+	fetch_cmd
+		.arg("-C")
+		.arg(dir)
+		.args(["fetch", "--quiet", "origin"]);
+
+	match rev {
+		Some(rev) => fetch_cmd.arg(rev),
+		None => fetch_cmd.args(["--depth=1", "HEAD"]),
+	};
+
+	run_git(
+		&mut fetch_cmd,
+		&format!("fetch the latest commits of `{repo}`"),
+	)?;
+
+	run_git(
+		Command::new("git").arg("-C").arg(dir).args([
+			"checkout",
+			"--quiet",
+			"--force",
+			"--detach",
+			"FETCH_HEAD",
+		]),
+		&format!("check out the latest commits of `{repo}`"),
+	)?;
+
+	mark_as_fetched(dir)
+}
+
+/// How long a clone is used as it is, before its revision is fetched again.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The file whose modification time records the last time that a clone was fetched.
+const FETCHED_MARKER: &str = ".git/sketch-fetched";
+
+fn mark_as_fetched(dir: &Path) -> AppResult {
+	std::fs::write(dir.join(FETCHED_MARKER), "")
+		.with_context(|| format!("Could not update the clone in `{}`", dir.display()))?;
+
+	Ok(())
+}
+
+/// Whether a clone was fetched too long ago, and should be refreshed.
+pub(crate) fn needs_refresh(dir: &Path) -> bool {
+	std::fs::metadata(dir.join(FETCHED_MARKER))
+		.and_then(|metadata| metadata.modified())
+		.ok()
+		.and_then(|fetched_at| SystemTime::now().duration_since(fetched_at).ok())
+		.is_none_or(|elapsed| elapsed > REFRESH_INTERVAL)
+}
+
+/// Whether a revision is a commit hash (full or abbreviated), which always points to the same content.
+pub(crate) fn is_commit_hash(rev: &str) -> bool {
+	(7..=40).contains(&rev.len()) && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Runs a git command. The action describes what the command does, for the error messages.
+fn run_git(cmd: &mut Command, action: &str) -> AppResult {
+	let output = cmd
+		.output()
+		.with_context(|| format!("Could not {action}"))?;
+
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(anyhow!("Could not {action}: {stderr}").into());
+	}
+
+	Ok(())
+}
@@ -35,6 +35,10 @@ pub struct RepoPreset {
   )]
 	/// One or many workflows to generate in the new repo.
 	pub workflows: Vec<GhWorkflowPresetRef>,
+
+	#[arg(skip)]
+	/// The policy for the files generated by this preset that already exist. It overrides the global policy, but not the `overwrite_rules`.
+	pub overwrite: Option<OverwritePolicy>,
 }
 
 impl std::str::FromStr for PreCommitSetting {
@@ -46,35 +50,68 @@ impl std::str::FromStr for PreCommitSetting {
 }
 
 impl Config {
+	/// Generates a new git repo with the given preset.
+	///
+	/// If any of the steps fails, the files and directories created up to that point are removed, and the files that were overwritten are restored.
 	pub fn init_repo(
 		self,
 		preset: RepoPreset,
 		remote: Option<&str>,
 		out_dir: &Path,
 		cli_vars: &IndexMap<String, Value>,
+		manifest: &mut GenerationManifest,
+	) -> Result<(), AppError> {
+		with_written_files_sync(|| {
+			with_transaction_sync(|| {
+				self.generate_repo(preset, remote, out_dir, cli_vars, manifest)
+			})
+		})
+	}
+
+	fn generate_repo(
+		self,
+		preset: RepoPreset,
+		remote: Option<&str>,
+		out_dir: &Path,
+		cli_vars: &IndexMap<String, Value>,
+		manifest: &mut GenerationManifest,
 	) -> Result<(), AppError> {
 		let overwrite = self.can_overwrite();
 
 		create_all_dirs(out_dir)?;
 
-		let gitignore = if let Some(preset_ref) = preset.gitignore {
+		let (gitignore, gitignore_id) = if let Some(preset_ref) = preset.gitignore {
 			match preset_ref {
-				GitIgnorePresetRef::PresetId(id) => self.get_gitignore_preset(&id)?.content,
-				GitIgnorePresetRef::Preset(preset) => {
+				GitIgnorePresetRef::PresetId(id) => {
+					(self.get_gitignore_preset(&id)?.content, Some(id))
+				}
+				GitIgnorePresetRef::Preset(preset) => (
 					preset
 						.merge_presets("__inlined", &self.gitignore_presets)?
-						.content
-				}
+						.content,
+					Some("__inlined".to_string()),
+				),
 			}
 		} else {
-			GitIgnore::String(DEFAULT_GITIGNORE.trim().to_string())
+			(
+				GitIgnore::String(DEFAULT_GITIGNORE.trim().to_string()),
+				None,
+			)
 		};
 
-		write_file(
-			&out_dir.join(".gitignore"),
-			&gitignore.to_string(),
-			overwrite,
-		)?;
+		let gitignore_path = out_dir.join(".gitignore");
+
+		write_file(&gitignore_path, &gitignore.to_string(), overwrite)?;
+
+		if gitignore_id.is_some() {
+			manifest.record_file(
+				&gitignore_path,
+				PresetKind::Gitignore,
+				gitignore_id.as_deref(),
+			)?;
+		} else {
+			manifest.record_main_file(&gitignore_path)?;
+		}
 
 		launch_command(
 			"git",
@@ -86,20 +123,25 @@ impl Config {
 		if let Some(pre_commit) = preset.pre_commit
 			&& pre_commit.is_enabled()
 		{
-			let pre_commit_config = match pre_commit {
-				PreCommitSetting::Id(id) => self.get_pre_commit_preset(&id)?.config,
-				PreCommitSetting::Bool(_) => PreCommitConfig::default(),
-				PreCommitSetting::Config(preset) => {
+			let (pre_commit_config, pre_commit_id) = match pre_commit {
+				PreCommitSetting::Id(id) => (self.get_pre_commit_preset(&id)?.config, Some(id)),
+				PreCommitSetting::Bool(_) => (PreCommitConfig::default(), None),
+				PreCommitSetting::Config(preset) => (
 					preset
 						.merge_presets("__inlined", &self.pre_commit_presets)?
-						.config
-				}
+						.config,
+					Some("__inlined".to_string()),
+				),
 			};
 
-			serialize_yaml(
-				&pre_commit_config,
-				&out_dir.join(".pre-commit-config.yaml"),
-				overwrite,
+			let pre_commit_path = out_dir.join(".pre-commit-config.yaml");
+
+			serialize_yaml(&pre_commit_config, &pre_commit_path, overwrite)?;
+
+			manifest.record_file(
+				&pre_commit_path,
+				PresetKind::PreCommit,
+				pre_commit_id.as_deref(),
 			)?;
 
 			launch_command(
@@ -120,7 +162,11 @@ impl Config {
 		}
 
 		if let Some(license) = preset.license {
-			write_file(&out_dir.join("LICENSE"), license.get_content(), overwrite)?;
+			let license_path = out_dir.join("LICENSE");
+
+			write_file(&license_path, license.get_content(), overwrite)?;
+
+			manifest.record_main_file(&license_path)?;
 		}
 
 		if !preset.workflows.is_empty() {
@@ -132,7 +178,15 @@ impl Config {
 					GhWorkflowPresetRef::PresetId { file_name, id } => {
 						let data = self.github.get_workflow(&id)?;
 
-						serialize_yaml(&data, &workflows_dir.join(file_name), overwrite)?;
+						let workflow_path = workflows_dir.join(file_name);
+
+						serialize_yaml(&data, &workflow_path, overwrite)?;
+
+						manifest.record_file(
+							&workflow_path,
+							PresetKind::GithubWorkflow,
+							Some(&id),
+						)?;
 					}
 					GhWorkflowPresetRef::Preset {
 						file_name,
@@ -140,14 +194,27 @@ impl Config {
 					} => {
 						let data = config.process_data("__inlined", &self.github)?;
 
-						serialize_yaml(&data, &workflows_dir.join(file_name), overwrite)?;
+						let workflow_path = workflows_dir.join(file_name);
+
+						serialize_yaml(&data, &workflow_path, overwrite)?;
+
+						manifest.record_file(
+							&workflow_path,
+							PresetKind::GithubWorkflow,
+							Some("__inlined"),
+						)?;
 					}
 				}
 			}
 		}
 
 		if !preset.with_templates.is_empty() {
-			self.generate_templates(out_dir, preset.with_templates, cli_vars)?;
+			self.render_templating_presets(
+				out_dir,
+				preset.with_templates,
+				cli_vars,
+				Some(manifest),
+			)?;
 		}
 
 		Ok(())
@@ -157,9 +224,8 @@ impl Config {
 		Ok(self
 			.repo_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::Repo,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::Repo, id, self.repo_presets.keys())
 			})?
 			.clone())
 	}
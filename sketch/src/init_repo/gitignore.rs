@@ -4,9 +4,8 @@ impl Config {
 	pub fn get_gitignore_preset(&self, id: &str) -> AppResult<GitignorePreset> {
 		self.gitignore_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::Gitignore,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::Gitignore, id, self.gitignore_presets.keys())
 			})?
 			.clone()
 			.merge_presets(id, &self.gitignore_presets)
@@ -67,6 +66,10 @@ impl ExtensiblePreset for GitignorePreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
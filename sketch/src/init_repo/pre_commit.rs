@@ -28,9 +28,12 @@ impl Config {
 	pub fn get_pre_commit_preset(&self, id: &str) -> AppResult<PreCommitPreset> {
 		self.pre_commit_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::PreCommit,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(
+					PresetKind::PreCommit,
+					id,
+					self.pre_commit_presets.keys(),
+				)
 			})?
 			.clone()
 			.merge_presets(id, &self.pre_commit_presets)
@@ -45,6 +48,10 @@ impl ExtensiblePreset for PreCommitPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 fn default_pre_commit() -> PreCommitConfig {
@@ -45,6 +45,7 @@ mod macros;
 
 pub(crate) mod exec;
 pub(crate) mod fs;
+pub(crate) mod git;
 pub(crate) mod init_repo;
 pub(crate) mod merging_strategies;
 pub(crate) mod serde_utils;
@@ -54,8 +55,11 @@ pub mod cli;
 pub mod config;
 pub mod docker;
 pub mod errors;
+pub mod generator;
 pub mod gh_workflow;
 pub mod licenses;
+pub mod manifest;
+pub mod output;
 pub mod rust;
 pub mod ts;
 
@@ -63,11 +67,14 @@ pub mod ts;
 pub use config::*;
 #[doc(inline)]
 pub use errors::*;
+pub(crate) use manifest::*;
 pub(crate) use merging_strategies::*;
+pub(crate) use output::*;
 pub(crate) use templating::*;
 
 /// The kinds of presets supported by `sketch`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum PresetKind {
 	PackageJson,
 	TsPackage,
@@ -87,3 +94,11 @@ pub enum PresetKind {
 	GithubWorkflowStep,
 	RustCrate,
 }
+
+/// The serialization formats supported by `sketch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+	Yaml,
+	Json,
+	Toml,
+}
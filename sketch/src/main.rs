@@ -1,16 +1,21 @@
 #![allow(clippy::result_large_err)]
 
 use clap::{CommandFactory, error::ErrorKind};
+use clap_complete::CompleteEnv;
 use sketch_it::cli::Cli;
 
 #[tokio::main]
 async fn main() {
+	// Handles the requests from the scripts generated with `sketch completions`
+	CompleteEnv::with_factory(Cli::command).complete();
+
 	match sketch_it::cli::main_entrypoint().await {
 		Ok(()) => {}
 		Err(e) => {
 			let mut cmd = Cli::command();
 
-			cmd.error(ErrorKind::InvalidValue, e).exit();
+			cmd.error(ErrorKind::InvalidValue, format!("[{}] {e}", e.code()))
+				.exit();
 		}
 	}
 }
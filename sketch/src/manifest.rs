@@ -0,0 +1,340 @@
+use std::{cell::RefCell, future::Future};
+
+use sha2::{Digest, Sha256};
+
+use crate::*;
+
+tokio::task_local! {
+	static WRITTEN_FILES: RefCell<IndexMap<PathBuf, Vec<u8>>>;
+}
+
+fn is_tracking_written_files() -> bool {
+	WRITTEN_FILES.try_with(|_| ()).is_ok()
+}
+
+/// Runs a future while keeping the content of the files that it writes, so that the manifest can record what was actually written.
+///
+/// If the written files are already being tracked, the future uses the same records.
+pub(crate) async fn with_written_files<F: Future>(future: F) -> F::Output {
+	if is_tracking_written_files() {
+		return future.await;
+	}
+
+	WRITTEN_FILES
+		.scope(RefCell::default(), future)
+		.await
+}
+
+/// The synchronous version of [`with_written_files`].
+pub(crate) fn with_written_files_sync<T>(f: impl FnOnce() -> T) -> T {
+	if is_tracking_written_files() {
+		return f();
+	}
+
+	WRITTEN_FILES.sync_scope(RefCell::default(), f)
+}
+
+/// Keeps the content of a file that has just been written.
+pub(crate) fn track_written_file(path: &Path, content: &[u8]) {
+	let _ = WRITTEN_FILES.try_with(|files| {
+		files
+			.borrow_mut()
+			.insert(virtual_path(path), content.to_vec());
+	});
+}
+
+/// Returns the content that was written to a file, or `None` if it was not written.
+fn written_content(path: &Path) -> AppResult<Option<Vec<u8>>> {
+	WRITTEN_FILES
+		.try_with(|files| files.borrow().get(&virtual_path(path)).cloned())
+		.map_err(|_| {
+			anyhow!(
+				"Cannot record `{}` in the generation manifest, because the written files are not being tracked",
+				path.display()
+			)
+			.into()
+		})
+}
+
+/// The path of the generation manifest, relative to the root of the generated files.
+pub const MANIFEST_PATH: &str = ".sketch/manifest";
+
+/// The directory where a copy of the generated files is stored, relative to the root of the generated files. It is used as the common ancestor when the files are updated.
+pub const GENERATED_FILES_DIR: &str = ".sketch/generated";
+
+/// A record of the files produced by a generation command, which is stored in the output directory.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct GenerationManifest {
+	/// The version of `sketch` that generated the files.
+	pub sketch_version: String,
+
+	/// The command that generated the files.
+	pub command: GenerationCommand,
+
+	/// The variables that were set via cli or with vars files.
+	#[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+	pub vars: IndexMap<String, Value>,
+
+	/// The generated files.
+	pub files: Vec<ManifestEntry>,
+
+	#[serde(skip)]
+	root: PathBuf,
+}
+
+/// A file produced by a generation command.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ManifestEntry {
+	/// The path to the file, relative to the output directory.
+	pub path: PathBuf,
+
+	/// The kind of the preset that produced this file.
+	pub preset_kind: PresetKind,
+
+	/// The id of the preset that produced this file. Presets defined inline have the `__inlined` id.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub preset_id: Option<String>,
+
+	/// The name of the template that was rendered, if this file was produced by a template.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub template: Option<String>,
+
+	/// The variables used to render the template. The values that were interpolated from environment variables in the config are stored as they are.
+	#[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+	pub vars: IndexMap<String, Value>,
+
+	/// The SHA-256 hash of the contents of the file.
+	pub hash: String,
+
+	#[serde(skip)]
+	pub(crate) content: Option<Vec<u8>>,
+}
+
+/// The generation command (and its presets) recorded in a manifest, so that the files can be generated again with `sketch update`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum GenerationCommand {
+	Repo {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preset: Option<String>,
+	},
+	RustCrate {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preset: Option<String>,
+
+		/// The id of the `Cargo.toml` preset selected via cli.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		manifest: Option<String>,
+
+		/// The name of the crate, if it was set via cli.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		name: Option<String>,
+	},
+	TsPackage {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		preset: Option<String>,
+
+		/// The id of the vitest preset selected via cli.
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		vitest: Option<String>,
+	},
+	TsMonorepo {
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		root_package: Option<String>,
+
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pnpm: Option<String>,
+	},
+}
+
+impl GenerationCommand {
+	/// The kind of the main preset used by the command.
+	#[must_use]
+	pub const fn preset_kind(&self) -> PresetKind {
+		match self {
+			Self::Repo { .. } => PresetKind::Repo,
+			Self::RustCrate { .. } => PresetKind::RustCrate,
+			Self::TsPackage { .. } | Self::TsMonorepo { .. } => PresetKind::TsPackage,
+		}
+	}
+
+	/// The id of the main preset used by the command, if one was selected.
+	#[must_use]
+	pub fn preset_id(&self) -> Option<&str> {
+		match self {
+			Self::Repo { preset }
+			| Self::RustCrate { preset, .. }
+			| Self::TsPackage { preset, .. } => preset.as_deref(),
+			Self::TsMonorepo { root_package, .. } => root_package.as_deref(),
+		}
+	}
+}
+
+pub(crate) fn hash_content(content: &[u8]) -> String {
+	format!("{:x}", Sha256::digest(content))
+}
+
+impl GenerationManifest {
+	/// Creates a new manifest for the files that will be generated in `root`.
+	pub fn new(root: &Path, command: GenerationCommand, vars: &IndexMap<String, Value>) -> Self {
+		Self {
+			sketch_version: env!("CARGO_PKG_VERSION").to_string(),
+			command,
+			vars: vars.clone(),
+			files: Vec::new(),
+			root: root.to_path_buf(),
+		}
+	}
+
+	/// Reads the manifest stored in a directory, if there is one.
+	pub fn from_dir(root: &Path) -> AppResult<Option<Self>> {
+		let path = root.join(MANIFEST_PATH);
+
+		if !file_exists(&path) {
+			return Ok(None);
+		}
+
+		let mut manifest: Self = deserialize_json(&path)?;
+
+		manifest.root = root.to_path_buf();
+
+		for entry in &mut manifest.files {
+			let copy_path = root.join(GENERATED_FILES_DIR).join(&entry.path);
+
+			if file_exists(&copy_path) {
+				entry.content = Some(read_bytes(&copy_path)?);
+			}
+		}
+
+		Ok(Some(manifest))
+	}
+
+	/// Returns the entry for a file, if it was generated.
+	#[must_use]
+	pub fn get_entry(&self, path: &Path) -> Option<&ManifestEntry> {
+		self.files.iter().find(|entry| entry.path == path)
+	}
+
+	/// Replaces the entry of a file with its entry from another manifest, or removes it if there is none.
+	pub(crate) fn restore_entry(&mut self, path: &Path, previous: Option<&ManifestEntry>) {
+		match (
+			self.files
+				.iter()
+				.position(|entry| entry.path == path),
+			previous,
+		) {
+			(Some(idx), Some(previous)) => self.files[idx] = previous.clone(),
+			(Some(idx), None) => {
+				self.files.remove(idx);
+			}
+			(None, Some(previous)) => self.files.push(previous.clone()),
+			(None, None) => {}
+		}
+	}
+
+	pub(crate) fn set_root(&mut self, root: &Path) {
+		self.root = root.to_path_buf();
+	}
+
+	/// Records a file generated by a preset, with the content that was written to it. Files outside of the output directory, and the files that were not written, are ignored.
+	pub(crate) fn record_file(
+		&mut self,
+		path: &Path,
+		preset_kind: PresetKind,
+		preset_id: Option<&str>,
+	) -> AppResult {
+		self.record(ManifestEntry {
+			path: path.to_path_buf(),
+			preset_kind,
+			preset_id: preset_id.map(str::to_string),
+			template: None,
+			vars: IndexMap::new(),
+			hash: String::new(),
+			content: None,
+		})
+	}
+
+	/// Records a file that was generated with one of the templates of a templating preset.
+	pub(crate) fn record_template(
+		&mut self,
+		path: &Path,
+		preset_id: &str,
+		template: &str,
+		vars: &IndexMap<String, Value>,
+	) -> AppResult {
+		self.record(ManifestEntry {
+			path: path.to_path_buf(),
+			preset_kind: PresetKind::Templates,
+			preset_id: Some(preset_id.to_string()),
+			template: Some(template.to_string()),
+			vars: vars.clone(),
+			hash: String::new(),
+			content: None,
+		})
+	}
+
+	/// Records a file generated directly by the main preset.
+	pub(crate) fn record_main_file(&mut self, path: &Path) -> AppResult {
+		let preset_kind = self.command.preset_kind();
+		let preset_id = self.command.preset_id().map(str::to_string);
+
+		self.record_file(path, preset_kind, preset_id.as_deref())
+	}
+
+	fn record(&mut self, mut entry: ManifestEntry) -> AppResult {
+		let root = get_abs_path(&self.root)?;
+		let path = get_abs_path(&entry.path)?;
+
+		let Ok(rel_path) = path.strip_prefix(&root) else {
+			return Ok(());
+		};
+
+		// A file that was skipped because of an overwrite policy still has the content set by the user, which must not be used as the base of the next update
+		let Some(content) = written_content(&entry.path)? else {
+			return Ok(());
+		};
+
+		entry.hash = hash_content(&content);
+		entry.path = rel_path.to_path_buf();
+		entry.content = Some(content);
+
+		// If a file is written more than once, only the last write is recorded
+		self.files
+			.retain(|existing| existing.path != entry.path);
+		self.files.push(entry);
+
+		Ok(())
+	}
+
+	/// Writes the manifest in the output directory, along with a copy of the generated files.
+	pub fn write(&self) -> AppResult {
+		// These files are managed by sketch, so their changes are not reviewed and they are always overwritten
+		without_review(|| {
+			without_overwrite_policies(|| without_current_preset(|| self.write_files()))
+		})
+	}
+
+	fn write_files(&self) -> AppResult {
+		let path = self.root.join(MANIFEST_PATH);
+
+		create_parent_dirs(&path)?;
+
+		let copies_dir = self.root.join(GENERATED_FILES_DIR);
+
+		if path_exists(&copies_dir) {
+			remove_all(&copies_dir)?;
+		}
+
+		for entry in &self.files {
+			if let Some(content) = &entry.content {
+				let copy_path = copies_dir.join(&entry.path);
+
+				create_parent_dirs(&copy_path)?;
+
+				write_bytes(&copy_path, content, true)?;
+			}
+		}
+
+		serialize_json(self, &path, true)
+	}
+}
@@ -5,6 +5,9 @@ pub trait ExtensiblePreset: Merge + Sized + Clone {
 
 	fn extended_ids(&mut self) -> &mut IndexSet<String>;
 
+	/// The ids of the presets that this preset extends.
+	fn extended_preset_ids(&self) -> &IndexSet<String>;
+
 	fn merge_presets(
 		self,
 		current_id: &str,
@@ -14,38 +17,73 @@ pub trait ExtensiblePreset: Merge + Sized + Clone {
 	}
 
 	fn merge_presets_recursive(
-		mut self,
+		self,
 		current_id: &str,
 		store: &IndexMap<String, Self>,
 		processed_ids: &mut IndexSet<String>,
 	) -> Result<Self, AppError> {
-		let presets_to_extend = self.extended_ids().clone();
+		merge_presets_observed(self, current_id, store, processed_ids, &mut ())
+	}
+}
+
+/// Observes the steps of [`ExtensiblePreset::merge_presets_recursive`].
+pub(crate) trait MergeObserver<T> {
+	/// Called before a preset (the overlay) is merged on top of one of the presets that it extends (the base), which has already been resolved.
+	fn before_merge(
+		&mut self,
+		_id: &str,
+		_extended_id: &str,
+		_base: &T,
+		_overlay: &T,
+	) -> AppResult {
+		Ok(())
+	}
+
+	/// Called with the result of the merge of a preset on top of one of the presets that it extends.
+	fn after_merge(&mut self, _id: &str, _extended_id: &str, _merged: &T) -> AppResult {
+		Ok(())
+	}
+}
+
+impl<T> MergeObserver<T> for () {}
+
+/// Merges a preset with the presets that it extends, while notifying an observer of each step.
+pub(crate) fn merge_presets_observed<T: ExtensiblePreset>(
+	mut preset: T,
+	current_id: &str,
+	store: &IndexMap<String, T>,
+	processed_ids: &mut IndexSet<String>,
+	observer: &mut impl MergeObserver<T>,
+) -> Result<T, AppError> {
+	let presets_to_extend = preset.extended_ids().clone();
 
-		if presets_to_extend.is_empty() {
-			return Ok(self);
-		}
+	if presets_to_extend.is_empty() {
+		return Ok(preset);
+	}
+
+	check_for_circular_dependencies(current_id, processed_ids, T::kind())?;
 
-		check_for_circular_dependencies(current_id, processed_ids, Self::kind())?;
+	for id in &presets_to_extend {
+		let extend_target = store
+			.get(id)
+			.ok_or_else(|| AppError::preset_not_found(T::kind(), id, store.keys()))?
+			.clone();
 
-		for id in &presets_to_extend {
-			let mut extend_target = store
-				.get(id)
-				.ok_or_else(|| AppError::PresetNotFound {
-					kind: Self::kind(),
-					name: id.clone(),
-				})?
-				.clone()
-				.merge_presets_recursive(id, store, processed_ids)?;
+		let mut extend_target =
+			merge_presets_observed(extend_target, id, store, processed_ids, observer)?;
 
-			extend_target.merge(self);
+		observer.before_merge(current_id, id, &extend_target, &preset)?;
 
-			self = extend_target;
-		}
+		extend_target.merge(preset);
 
-		*self.extended_ids() = presets_to_extend;
+		observer.after_merge(current_id, id, &extend_target)?;
 
-		Ok(self)
+		preset = extend_target;
 	}
+
+	*preset.extended_ids() = presets_to_extend;
+
+	Ok(preset)
 }
 
 fn check_for_circular_dependencies(
@@ -56,11 +94,12 @@ fn check_for_circular_dependencies(
 	let was_absent = processed_ids.insert(id.to_string());
 
 	if !was_absent {
-		let chain: Vec<&str> = processed_ids.iter().map(|s| s.as_str()).collect();
+		let mut chain: Vec<String> = processed_ids.iter().cloned().collect();
+		chain.push(id.to_string());
 
-		return Err(AppError::CircularDependency(format!(
-			"Found circular {preset_kind:?} dependency for preset with id '{id}'. The full processed chain is: {}",
-			chain.join(" -> ")
+		return Err(AppError::CircularDependency(DependencyCycle::new(
+			Some(preset_kind),
+			chain,
 		)));
 	}
 
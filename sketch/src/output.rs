@@ -0,0 +1,194 @@
+//! The events emitted while running a command, which can be printed as json lines with `--output-format json`.
+
+use std::future::Future;
+
+use crate::*;
+
+tokio::task_local! {
+	static OUTPUT_FORMAT: OutputFormat;
+	static CURRENT_PRESET: Option<String>;
+}
+
+/// The format of the messages printed by `sketch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+	/// Human readable messages.
+	#[default]
+	Text,
+	/// One json object per line, for each event. The output of the shell commands is redirected to stderr.
+	Json,
+}
+
+/// An event that happened while running a command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum OutputEvent {
+	/// A new file was written.
+	FileCreated {
+		path: PathBuf,
+		/// The preset that generated the file, if there is one.
+		preset: Option<String>,
+	},
+	/// An existing file was overwritten.
+	FileOverwritten {
+		path: PathBuf,
+		preset: Option<String>,
+	},
+	/// An existing file was left untouched because of its overwrite policy.
+	FileSkipped {
+		path: PathBuf,
+		preset: Option<String>,
+	},
+	/// A shell command was launched.
+	CommandStarted {
+		command: String,
+		cwd: PathBuf,
+	},
+	/// A shell command exited.
+	CommandFinished {
+		command: String,
+		cwd: PathBuf,
+		/// The exit code, if the command was not terminated by a signal.
+		exit_code: Option<i32>,
+		success: bool,
+	},
+	/// A shell command that would be launched outside of dry-run mode.
+	CommandPlanned {
+		command: String,
+		cwd: PathBuf,
+	},
+	/// The ids of the presets of a given kind, printed by `list`.
+	PresetList {
+		kind: PresetKind,
+		ids: Vec<String>,
+	},
+	/// The differences between two presets, printed by `preset diff`.
+	PresetDiff {
+		changes: Vec<ValueChange>,
+	},
+	/// A resolved preset along with the origin of each value, printed by `preset explain`.
+	PresetExplanation(PresetExplanation),
+	/// The content printed by a command, like a preset printed by `show` or a template rendered to stdout.
+	Output {
+		content: String,
+	},
+	/// An informational message.
+	Message {
+		message: String,
+	},
+	Warning {
+		message: String,
+	},
+	/// The error that stopped the command.
+	Error {
+		/// The name of the [`AppError`] variant.
+		kind: String,
+		/// The code returned by [`AppError::code`].
+		code: String,
+		message: String,
+	},
+}
+
+impl OutputEvent {
+	pub(crate) fn error(error: &AppError) -> Self {
+		Self::Error {
+			kind: error.kind().to_string(),
+			code: error.code().to_string(),
+			message: error.to_string(),
+		}
+	}
+
+	/// Prints the event in the text format. The events for the files and the commands are only printed in the json format.
+	fn print_text(self) {
+		match self {
+			Self::PresetList { ids, .. } => {
+				for id in ids {
+					println!("{id}");
+				}
+			}
+			Self::PresetDiff { changes } => {
+				for change in changes {
+					println!("{change}");
+				}
+			}
+			Self::PresetExplanation(explanation) => print!("{explanation}"),
+			Self::Output { content } => println!("{content}"),
+			Self::Message { message } => println!("{message}"),
+			Self::Warning { message } => eprintln!("Warning: {message}"),
+			_ => {}
+		}
+	}
+}
+
+/// Runs a future where the events are printed in the given format.
+pub(crate) async fn with_output_format<F: Future>(format: OutputFormat, future: F) -> F::Output {
+	OUTPUT_FORMAT.scope(format, future).await
+}
+
+pub(crate) fn is_json_output() -> bool {
+	OUTPUT_FORMAT
+		.try_with(|format| *format == OutputFormat::Json)
+		.unwrap_or(false)
+}
+
+/// Runs a future where the events for the generated files refer to the given preset.
+pub(crate) async fn with_current_preset<F: Future>(preset: Option<&str>, future: F) -> F::Output {
+	match preset {
+		Some(preset) => {
+			CURRENT_PRESET
+				.scope(Some(preset.to_string()), future)
+				.await
+		}
+		None => future.await,
+	}
+}
+
+/// The synchronous version of [`with_current_preset`].
+pub(crate) fn with_current_preset_sync<T>(preset: Option<&str>, f: impl FnOnce() -> T) -> T {
+	match preset {
+		Some(preset) => CURRENT_PRESET.sync_scope(Some(preset.to_string()), f),
+		None => f(),
+	}
+}
+
+/// Runs a function where the events for the generated files do not refer to any preset, which is used for the files managed internally by `sketch`.
+pub(crate) fn without_current_preset<T>(f: impl FnOnce() -> T) -> T {
+	CURRENT_PRESET.sync_scope(None, f)
+}
+
+pub(crate) fn current_preset() -> Option<String> {
+	CURRENT_PRESET
+		.try_with(Clone::clone)
+		.ok()
+		.flatten()
+}
+
+/// Prints an informational message, or emits it as an event in the json output.
+pub(crate) fn emit_message(message: impl Display) {
+	emit(OutputEvent::Message {
+		message: message.to_string(),
+	});
+}
+
+/// Prints an event in the current output format.
+///
+/// The events are only printed when running as the cli, so that the library does not write to stdout. Outside of the cli, only the warnings are printed (to stderr).
+pub(crate) fn emit(event: OutputEvent) {
+	let Ok(format) = OUTPUT_FORMAT.try_with(|format| *format) else {
+		if matches!(event, OutputEvent::Warning { .. }) {
+			event.print_text();
+		}
+
+		return;
+	};
+
+	if format == OutputFormat::Text {
+		event.print_text();
+		return;
+	}
+
+	match serde_json::to_string(&event) {
+		Ok(json) => println!("{json}"),
+		Err(e) => eprintln!("Warning: could not serialize the event {event:?}: {e}"),
+	}
+}
@@ -34,14 +34,29 @@ pub struct CratePreset {
 
 	#[arg(short = 't', long = "template", value_name = "PRESET_ID")]
 	pub with_templates: Vec<TemplatingPresetRef>,
+
+	#[arg(skip)]
+	/// The policy for the files generated by this preset that already exist. It overrides the global policy, but not the `overwrite_rules`.
+	pub overwrite: Option<OverwritePolicy>,
 }
 
 impl CratePreset {
 	pub fn generate(
 		self,
-		dir: &PathBuf,
+		dir: &Path,
 		name: Option<String>,
 		config: &Config,
+		generation_manifest: &mut GenerationManifest,
+	) -> Result<(), AppError> {
+		with_written_files_sync(|| self.generate_crate(dir, name, config, generation_manifest))
+	}
+
+	fn generate_crate(
+		self,
+		dir: &Path,
+		name: Option<String>,
+		config: &Config,
+		generation_manifest: &mut GenerationManifest,
 	) -> Result<(), AppError> {
 		create_all_dirs(dir)?;
 
@@ -66,19 +81,18 @@ impl CratePreset {
 
 		let workspace_manifest_path = get_parent_dir(dir)?.join("Cargo.toml");
 
-		let workspace_manifest = if !manifest_is_virtual && workspace_manifest_path.exists() {
-			let workspace_manifest_raw = read_to_string(&workspace_manifest_path).map_err(|e| {
-				AppError::DeserializationError {
-					file: workspace_manifest_path.clone(),
-					error: e.to_string(),
-				}
-			})?;
+		let workspace_manifest = if !manifest_is_virtual && file_exists(&workspace_manifest_path) {
+			let workspace_manifest_raw = read_file_to_string(&workspace_manifest_path)?;
 
 			let mut workspace_manifest_content = workspace_manifest_raw
 				.parse::<DocumentMut>()
-				.map_err(|e| AppError::DeserializationError {
-					file: workspace_manifest_path.clone(),
-					error: e.to_string(),
+				.map_err(|e| {
+					toml_deserialization_error(
+						&workspace_manifest_path,
+						&workspace_manifest_raw,
+						e.message(),
+						e.span(),
+					)
 				})?;
 
 			let workspace_entry = workspace_manifest_content
@@ -116,9 +130,13 @@ impl CratePreset {
 			)?;
 
 			let workspace_manifest_full: Manifest = toml::from_str(&workspace_manifest_raw)
-				.map_err(|e| AppError::DeserializationError {
-					file: workspace_manifest_path.clone(),
-					error: e.to_string(),
+				.map_err(|e| {
+					toml_deserialization_error(
+						&workspace_manifest_path,
+						&workspace_manifest_raw,
+						e.message(),
+						e.span(),
+					)
 				})?;
 
 			workspace_manifest_full.workspace
@@ -175,26 +193,35 @@ impl CratePreset {
 			}
 		}
 
-		write_file(
-			&dir.join("Cargo.toml"),
-			&manifest.as_document().to_string(),
-			true,
-		)?;
+		let manifest_path = dir.join("Cargo.toml");
+
+		write_file(&manifest_path, &manifest.as_document().to_string(), true)?;
+
+		generation_manifest.record_main_file(&manifest_path)?;
 
 		if let Some(GitIgnorePresetRef::Preset(gitignore)) = self.gitignore {
-			write_file(
-				&dir.join(".gitignore"),
-				&gitignore.content.to_string(),
-				true,
-			)?;
+			let gitignore_path = dir.join(".gitignore");
+
+			write_file(&gitignore_path, &gitignore.content.to_string(), true)?;
+
+			generation_manifest.record_main_file(&gitignore_path)?;
 		}
 
 		if let Some(license) = self.license {
-			write_file(&dir.join("LICENSE"), license.get_content(), true)?;
+			let license_path = dir.join("LICENSE");
+
+			write_file(&license_path, license.get_content(), true)?;
+
+			generation_manifest.record_main_file(&license_path)?;
 		}
 
 		if !self.with_templates.is_empty() {
-			config.generate_templates(dir, self.with_templates, &Default::default())?;
+			config.render_templating_presets(
+				dir,
+				self.with_templates,
+				&Default::default(),
+				Some(generation_manifest),
+			)?;
 		}
 
 		Ok(())
@@ -206,9 +233,8 @@ impl RustConfig {
 		Ok(self
 			.crate_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::RustCrate,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::RustCrate, id, self.crate_presets.keys())
 			})?
 			.clone())
 	}
@@ -216,9 +242,8 @@ impl RustConfig {
 	pub fn get_cargo_toml_preset(&self, id: &str) -> AppResult<CargoTomlPreset> {
 		self.manifest_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::CargoToml,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::CargoToml, id, self.manifest_presets.keys())
 			})?
 			.clone()
 			.merge_presets(id, &self.manifest_presets)
@@ -314,4 +339,8 @@ impl ExtensiblePreset for CargoTomlPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
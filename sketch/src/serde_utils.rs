@@ -1,3 +1,5 @@
+use serde::de::DeserializeOwned;
+
 use crate::*;
 
 pub(crate) fn deserialize_vars_file(path: &Path) -> AppResult<IndexMap<String, Value>> {
@@ -21,3 +23,69 @@ pub(crate) fn deserialize_vars_file(path: &Path) -> AppResult<IndexMap<String, V
 
 	Ok(map)
 }
+
+/// Recursively removes the fields of an object that are set to `null`.
+pub(crate) fn remove_nulls(value: &mut Value) {
+	match value {
+		Value::Object(map) => {
+			map.retain(|_, value| !value.is_null());
+			map.values_mut().for_each(remove_nulls);
+		}
+		Value::Array(items) => items.iter_mut().for_each(remove_nulls),
+		_ => {}
+	}
+}
+
+impl Format {
+	/// Serializes an item to a string in this format.
+	pub(crate) fn serialize<T: Serialize>(self, item: &T) -> AppResult<String> {
+		let output = match self {
+			Self::Yaml => serde_yaml_ng::to_string(item).map_err(|e| e.to_string()),
+			Self::Json => serde_json::to_string_pretty(item).map_err(|e| e.to_string()),
+			Self::Toml => toml::to_string_pretty(item).map_err(|e| e.to_string()),
+		};
+
+		Ok(output.map_err(|e| anyhow!("Could not serialize to {self:?}: {e}"))?)
+	}
+
+	/// Serializes an item to a file in this format.
+	pub(crate) fn serialize_to_file<T: Serialize>(
+		self,
+		item: &T,
+		path: &Path,
+		overwrite: bool,
+	) -> AppResult {
+		match self {
+			Self::Yaml => serialize_yaml(item, path, overwrite),
+			Self::Json => serialize_json(item, path, overwrite),
+			Self::Toml => serialize_toml(item, path, overwrite),
+		}
+	}
+
+	/// Deserializes the contents of a file in this format.
+	pub(crate) fn deserialize<T: DeserializeOwned>(
+		self,
+		path: &Path,
+		contents: &str,
+	) -> AppResult<T> {
+		match self {
+			Self::Yaml => parse_yaml(path, contents),
+			Self::Json => parse_json(path, contents),
+			Self::Toml => parse_toml(path, contents),
+		}
+	}
+
+	/// Detects the format of a file from its extension.
+	pub(crate) fn from_path(path: &Path) -> AppResult<Self> {
+		match get_extension(path)?.to_string_lossy().as_ref() {
+			"yaml" | "yml" => Ok(Self::Yaml),
+			"toml" => Ok(Self::Toml),
+			"json" => Ok(Self::Json),
+			_ => Err(anyhow!(
+				"Invalid format for `{}`. Allowed formats are: yaml, toml, json",
+				path.display()
+			)
+			.into()),
+		}
+	}
+}
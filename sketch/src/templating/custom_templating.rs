@@ -1,10 +1,14 @@
 use super::*;
+use crate::git::clone_repo;
 
 pub(crate) struct RenderCtx<'a> {
 	pub tera: &'a mut Tera,
 	pub overwrite: bool,
 	pub context: &'a Context,
 	pub output_root: &'a Path,
+	pub preset_id: &'a str,
+	pub vars: &'a IndexMap<String, Value>,
+	pub manifest: Option<&'a mut GenerationManifest>,
 }
 
 impl Config {
@@ -15,17 +19,31 @@ impl Config {
 		preset_refs: Vec<TemplatingPresetRef>,
 		cli_overrides: &IndexMap<String, Value>,
 	) -> Result<(), AppError> {
-		let output_root = output_root.as_ref();
+		self.render_templating_presets(output_root.as_ref(), preset_refs, cli_overrides, None)
+	}
+
+	/// Renders the templating presets, and records the generated files in the manifest, if one is provided.
+	pub(crate) fn render_templating_presets(
+		&self,
+		output_root: &Path,
+		preset_refs: Vec<TemplatingPresetRef>,
+		cli_overrides: &IndexMap<String, Value>,
+		mut manifest: Option<&mut GenerationManifest>,
+	) -> AppResult {
 		let overwrite = self.can_overwrite();
 
 		let mut tera = self.initialize_tera()?;
-		let mut global_context = create_context(&self.vars)?;
+
+		let mut global_vars = self.vars.clone();
+		insert_var_defaults(&self.var_declarations, &mut global_vars, None);
+
+		let mut global_context = create_context(&global_vars)?;
 		global_context.extend(get_default_context());
 
-		let mut template_context = TemplateContext::new(&global_context, cli_overrides);
+		let mut presets: Vec<(String, TemplatingPreset)> = Vec::new();
 
 		for preset_ref in preset_refs {
-			let preset = match preset_ref {
+			let (id, mut preset) = match preset_ref {
 				TemplatingPresetRef::PresetId {
 					preset_id: id,
 					mut context,
@@ -34,17 +52,55 @@ impl Config {
 
 					preset.context.append(&mut context);
 
-					preset
+					(id, preset)
 				}
 				TemplatingPresetRef::Preset(mut preset) => {
 					if !preset.extends_presets.is_empty() {
 						preset = preset.merge_presets("__inlined", &self.templating_presets)?;
 					}
 
-					preset
+					("__inlined".to_string(), preset)
 				}
 			};
 
+			insert_var_defaults(
+				&preset.var_declarations,
+				&mut preset.context,
+				Some(&global_vars),
+			);
+
+			presets.push((id, preset));
+		}
+
+		self.fill_missing_vars(&mut tera, &mut presets, &global_context, cli_overrides)?;
+
+		// Every preset is checked before rendering anything, so that all the issues can be reported at once
+		let mut issues: Vec<VarIssue> = Vec::new();
+		let mut resolved_vars: Vec<IndexMap<String, Value>> = Vec::new();
+
+		for (id, preset) in &presets {
+			let mut preset_vars = global_vars.clone();
+			preset_vars.extend(preset.context.clone());
+			preset_vars.extend(cli_overrides.clone());
+
+			check_vars(&self.var_declarations, &preset_vars, None, &mut issues);
+			check_vars(
+				&preset.var_declarations,
+				&preset_vars,
+				Some(id),
+				&mut issues,
+			);
+
+			resolved_vars.push(preset_vars);
+		}
+
+		if !issues.is_empty() {
+			return Err(AppError::InvalidVars(issues));
+		}
+
+		let mut template_context = TemplateContext::new(&global_context, cli_overrides);
+
+		for ((id, preset), vars) in presets.into_iter().zip(&resolved_vars) {
 			template_context.apply_local_context(&preset.context);
 
 			let mut render_ctx = RenderCtx {
@@ -52,28 +108,37 @@ impl Config {
 				overwrite,
 				context: template_context.as_ref(),
 				output_root,
+				preset_id: &id,
+				vars,
+				manifest: manifest.as_deref_mut(),
 			};
 
-			for template in preset.templates {
-				match template {
-					TemplateKind::Remote(remote_preset) => {
-						render_ctx.render_remote_preset(&remote_preset)?;
-					}
-					TemplateKind::Single(template) => {
-						render_ctx.render_single_template(&template)?;
+			with_current_preset_sync(Some(&id), || {
+				with_preset_policy_sync(preset.overwrite, || -> AppResult {
+					for template in preset.templates {
+						match template {
+							TemplateKind::Remote(remote_preset) => {
+								render_ctx.render_remote_preset(&remote_preset)?;
+							}
+							TemplateKind::Single(template) => {
+								render_ctx.render_single_template(&template)?;
+							}
+
+							TemplateKind::Structured(StructuredPreset { dir, exclude }) => {
+								render_ctx.render_structured_preset(
+									&dir,
+									self.templates_dir
+										.as_ref()
+										.context("templates_dir not set")?,
+									&exclude,
+								)?;
+							}
+						};
 					}
 
-					TemplateKind::Structured(StructuredPreset { dir, exclude }) => {
-						render_ctx.render_structured_preset(
-							&dir,
-							self.templates_dir
-								.as_ref()
-								.context("templates_dir not set")?,
-							&exclude,
-						)?;
-					}
-				};
-			}
+					Ok(())
+				})
+			})?;
 		}
 
 		Ok(())
@@ -167,17 +232,24 @@ impl<'a> TemplateContext<'a> {
 }
 
 impl RenderCtx<'_> {
-	fn render_template(&self, template_name: &str, output_path: &Path) -> Result<(), AppError> {
+	fn render_template(&mut self, template_name: &str, output_path: &Path) -> Result<(), AppError> {
 		create_all_dirs(get_parent_dir(output_path)?)?;
 
-		let mut output_file = open_file_if_overwriting(self.overwrite, output_path)?;
-
-		self.tera
-			.render_to(template_name, self.context, &mut output_file)
+		let output = self
+			.tera
+			.render(template_name, self.context)
 			.map_err(|e| AppError::TemplateRendering {
 				template: template_name.to_string(),
 				source: e,
-			})
+			})?;
+
+		write_file(output_path, &output, self.overwrite)?;
+
+		if let Some(manifest) = self.manifest.as_deref_mut() {
+			manifest.record_template(output_path, self.preset_id, template_name, self.vars)?;
+		}
+
+		Ok(())
 	}
 
 	pub(crate) fn render_remote_preset(
@@ -194,18 +266,7 @@ impl RenderCtx<'_> {
 			})?;
 		}
 
-		let clone_result = Command::new("git")
-			.arg("clone")
-			.arg("--depth=1")
-			.arg(repo)
-			.arg(&tmp_dir)
-			.output()
-			.with_context(|| format!("Could not clone git repo `{repo}`"))?;
-
-		if !clone_result.status.success() {
-			let stderr = String::from_utf8_lossy(&clone_result.stderr);
-			return Err(anyhow!("Could not clone git repo `{repo}`: {stderr}").into());
-		}
+		clone_repo(repo, None, &tmp_dir)?;
 
 		remove_dir_all(tmp_dir.join(".git"))
 			.with_context(|| format!("Could not empty the directory `{}`", tmp_dir.display()))?;
@@ -225,7 +286,7 @@ impl RenderCtx<'_> {
 	}
 
 	pub(crate) fn render_structured_preset(
-		&self,
+		&mut self,
 		dir: &Path,
 		templates_dir: &Path,
 		exclude: &[String],
@@ -241,24 +302,7 @@ impl RenderCtx<'_> {
 			.into());
 		}
 
-		let exclude_glob = if exclude.is_empty() {
-			None
-		} else {
-			let mut glob_builder = GlobSetBuilder::new();
-
-			for pattern in exclude {
-				glob_builder.add(
-					Glob::new(pattern)
-						.with_context(|| format!("Could not parse glob pattern `{pattern}`"))?,
-				);
-			}
-
-			Some(
-				glob_builder
-					.build()
-					.context("Could not build globset")?,
-			)
-		};
+		let exclude_glob = build_globset(exclude)?;
 
 		let _: () = for entry in WalkDir::new(&root_dir)
 			.into_iter()
@@ -300,9 +344,11 @@ impl RenderCtx<'_> {
 					output_path_from_root_dir = output_path_from_root_dir.with_extension("");
 				}
 
+				let output_path = self.output_root.join(output_path_from_root_dir);
+
 				self.render_template(
 					&template_path_from_templates_dir.to_string_lossy(),
-					&self.output_root.join(output_path_from_root_dir),
+					&output_path,
 				)?;
 			}
 		};
@@ -337,10 +383,12 @@ impl RenderCtx<'_> {
 						source: e,
 					})?;
 
-				println!("{output}");
+				emit(OutputEvent::Output { content: output });
 			}
 			TemplateOutputKind::Path(path) => {
-				self.render_template(template_name, &self.output_root.join(path))?;
+				let output_path = self.output_root.join(path);
+
+				self.render_template(template_name, &output_path)?;
 			}
 		};
 
@@ -348,13 +396,99 @@ impl RenderCtx<'_> {
 	}
 }
 
+impl Config {
+	/// Asks for the values of the variables that are used or declared by the presets but are missing from their context, if the input is interactive.
+	fn fill_missing_vars(
+		&self,
+		tera: &mut Tera,
+		presets: &mut [(String, TemplatingPreset)],
+		global_context: &Context,
+		cli_overrides: &IndexMap<String, Value>,
+	) -> AppResult {
+		if !can_prompt(self.no_input) {
+			return Ok(());
+		}
+
+		let stdin = std::io::stdin();
+		let mut reader = stdin.lock();
+		let mut writer = std::io::stderr();
+
+		let mut answers: IndexMap<String, Option<Value>> = IndexMap::new();
+
+		for (_, preset) in presets.iter_mut() {
+			let mut missing_vars = preset_referenced_vars(tera, preset)?;
+
+			missing_vars.extend(preset.var_declarations.keys().cloned());
+			missing_vars.extend(
+				self.var_declarations
+					.iter()
+					.filter(|(_, declaration)| declaration.required)
+					.map(|(name, _)| name.clone()),
+			);
+
+			missing_vars.retain(|var| {
+				!global_context.contains_key(var)
+					&& !preset.context.contains_key(var)
+					&& !cli_overrides.contains_key(var)
+			});
+
+			for var in missing_vars {
+				let value = if let Some(answer) = answers.get(&var) {
+					answer.clone()
+				} else {
+					let declaration = preset
+						.var_declarations
+						.get(&var)
+						.or_else(|| self.var_declarations.get(&var));
+
+					let answer = prompt_var(&mut reader, &mut writer, &var, declaration)?;
+
+					answers.insert(var.clone(), answer.clone());
+
+					answer
+				};
+
+				if let Some(value) = value {
+					preset.context.insert(var, value);
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+pub(crate) fn build_globset(patterns: &[String]) -> AppResult<Option<GlobSet>> {
+	if patterns.is_empty() {
+		return Ok(None);
+	}
+
+	let mut glob_builder = GlobSetBuilder::new();
+
+	for pattern in patterns {
+		glob_builder.add(
+			Glob::new(pattern)
+				.with_context(|| format!("Could not parse glob pattern `{pattern}`"))?,
+		);
+	}
+
+	Ok(Some(
+		glob_builder
+			.build()
+			.context("Could not build globset")?,
+	))
+}
+
 impl Config {
 	pub fn get_templating_preset(&self, id: &str) -> AppResult<TemplatingPreset> {
 		self.templating_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::Templates,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(
+					PresetKind::Templates,
+					id,
+					self.templating_presets.keys(),
+				)
 			})?
 			.clone()
 			.merge_presets(id, &self.templating_presets)
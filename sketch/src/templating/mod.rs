@@ -1,6 +1,6 @@
 use crate::*;
 
-use globset::{Glob, GlobSetBuilder};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use tera::{Context, Error, Map, Tera, Value as TeraValue};
 use walkdir::WalkDir;
 
@@ -15,6 +15,15 @@ use tera_functions::*;
 pub(crate) mod tera_setup;
 use tera_setup::*;
 
+pub(crate) mod prompts;
+pub(crate) use prompts::*;
+
+pub(crate) mod var_declarations;
+pub(crate) use var_declarations::*;
+
+pub(crate) mod var_references;
+use var_references::*;
+
 pub(crate) fn templates_dir() -> PathBuf {
 	PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/templates"))
 }
@@ -64,6 +73,12 @@ pub struct TemplatingPreset {
 	// multiple templates and may set a context for them
 	/// Additional context for the templates in this preset. It overrides previously set values, but not values set via the cli.
 	pub context: IndexMap<String, Value>,
+
+	/// The declarations for the variables used by the templates in this preset. They are checked before rendering any template.
+	pub var_declarations: IndexMap<String, VarDeclaration>,
+
+	/// The policy for the files rendered by this preset that already exist. It overrides the global policy, but not the `overwrite_rules`.
+	pub overwrite: Option<OverwritePolicy>,
 }
 
 impl ExtensiblePreset for TemplatingPreset {
@@ -74,6 +89,10 @@ impl ExtensiblePreset for TemplatingPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -0,0 +1,114 @@
+use std::fmt::Write as _;
+use std::io::{BufRead, IsTerminal};
+
+use super::*;
+
+/// Whether the missing template variables can be requested interactively.
+pub(crate) fn can_prompt(no_input: bool) -> bool {
+	!no_input && std::io::stdin().is_terminal()
+}
+
+/// Asks for the value of a template variable, using its declaration (if there is one) to show the default value and the allowed choices.
+///
+/// Returns `None` if the variable is optional and no value was provided.
+pub(crate) fn prompt_var<R: BufRead, W: Write>(
+	reader: &mut R,
+	writer: &mut W,
+	name: &str,
+	declaration: Option<&VarDeclaration>,
+) -> AppResult<Option<Value>> {
+	let write_error = |e: std::io::Error| anyhow!("Failed to write the prompt for `{name}`: {e}");
+
+	let mut prompt = name.to_string();
+
+	if let Some(declaration) = declaration {
+		if let Some(description) = &declaration.description {
+			write!(prompt, " ({description})").unwrap();
+		}
+
+		if let Some(default) = &declaration.default {
+			write!(prompt, " [default: {}]", display_value(default)).unwrap();
+		}
+
+		if !declaration.allowed_values.is_empty() {
+			let choices: Vec<String> = declaration
+				.allowed_values
+				.iter()
+				.map(display_value)
+				.collect();
+
+			write!(prompt, " [choices: {}]", choices.join(", ")).unwrap();
+		}
+	}
+
+	// Undeclared vars are used in the templates, so they are always required
+	let is_required = declaration.is_none_or(|d| d.required);
+
+	loop {
+		write!(writer, "{prompt}: ").map_err(write_error)?;
+		writer.flush().map_err(write_error)?;
+
+		let mut input = String::new();
+
+		let bytes_read = reader
+			.read_line(&mut input)
+			.with_context(|| format!("Failed to read the value for `{name}`"))?;
+
+		if bytes_read == 0 {
+			return Err(
+				anyhow!("The input was closed before a value for `{name}` was provided").into(),
+			);
+		}
+
+		let input = input.trim();
+
+		if input.is_empty() {
+			if let Some(default) = declaration.and_then(|d| d.default.as_ref()) {
+				return Ok(Some(default.clone()));
+			} else if !is_required {
+				return Ok(None);
+			}
+
+			writeln!(writer, "A value is required").map_err(write_error)?;
+			continue;
+		}
+
+		match parse_input(input, declaration) {
+			Ok(value) => return Ok(Some(value)),
+			Err(message) => writeln!(writer, "{message}").map_err(write_error)?,
+		};
+	}
+}
+
+fn display_value(value: &Value) -> String {
+	match value {
+		Value::String(string) => string.clone(),
+		_ => value.to_string(),
+	}
+}
+
+fn parse_input(input: &str, declaration: Option<&VarDeclaration>) -> Result<Value, String> {
+	let Some(declaration) = declaration else {
+		return Ok(Value::String(input.to_string()));
+	};
+
+	if let Some(choice) = declaration
+		.allowed_values
+		.iter()
+		.find(|value| display_value(value) == input)
+	{
+		return Ok(choice.clone());
+	}
+
+	if !declaration.allowed_values.is_empty() {
+		return Err(format!("`{input}` is not one of the allowed choices"));
+	}
+
+	match declaration.kind {
+		None | Some(VarType::String) => Ok(Value::String(input.to_string())),
+		Some(kind) => serde_json::from_str::<Value>(input)
+			.ok()
+			.filter(|value| kind.matches(value))
+			.ok_or_else(|| format!("`{input}` is not a valid {}", kind.name())),
+	}
+}
@@ -0,0 +1,169 @@
+use regex::Regex;
+
+use super::*;
+
+/// The declaration of a template variable. Declared variables are checked before rendering any template.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct VarDeclaration {
+	/// The type of the variable.
+	#[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+	pub kind: Option<VarType>,
+
+	/// A description of the variable.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+
+	/// The value to use when the variable is not set.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub default: Option<Value>,
+
+	/// Whether the variable must be set (if it has no default).
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub required: bool,
+
+	/// The values that are allowed for this variable.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub allowed_values: Vec<Value>,
+
+	/// A regex that the variable must match. Only applies to strings.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub regex: Option<String>,
+}
+
+/// The types of template variables.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum VarType {
+	String,
+	Number,
+	Integer,
+	Boolean,
+	Array,
+	Object,
+}
+
+impl VarType {
+	pub(crate) const fn name(self) -> &'static str {
+		match self {
+			Self::String => "string",
+			Self::Number => "number",
+			Self::Integer => "integer",
+			Self::Boolean => "boolean",
+			Self::Array => "array",
+			Self::Object => "object",
+		}
+	}
+
+	pub(crate) fn matches(self, value: &Value) -> bool {
+		match self {
+			Self::String => value.is_string(),
+			Self::Number => value.is_number(),
+			Self::Integer => value.is_i64() || value.is_u64(),
+			Self::Boolean => value.is_boolean(),
+			Self::Array => value.is_array(),
+			Self::Object => value.is_object(),
+		}
+	}
+}
+
+const fn value_type_name(value: &Value) -> &'static str {
+	match value {
+		Value::Null => "null",
+		Value::Bool(_) => "boolean",
+		Value::Number(_) => "number",
+		Value::String(_) => "string",
+		Value::Array(_) => "array",
+		Value::Object(_) => "object",
+	}
+}
+
+/// Inserts the default values of the declared variables that are not set in `vars` or in `parent_vars`.
+pub(crate) fn insert_var_defaults(
+	declarations: &IndexMap<String, VarDeclaration>,
+	vars: &mut IndexMap<String, Value>,
+	parent_vars: Option<&IndexMap<String, Value>>,
+) {
+	for (name, declaration) in declarations {
+		if let Some(default) = &declaration.default
+			&& !vars.contains_key(name)
+			&& !parent_vars.is_some_and(|parent| parent.contains_key(name))
+		{
+			vars.insert(name.clone(), default.clone());
+		}
+	}
+}
+
+/// Checks the variables against their declarations, and collects all the violations.
+pub(crate) fn check_vars(
+	declarations: &IndexMap<String, VarDeclaration>,
+	vars: &IndexMap<String, Value>,
+	preset: Option<&str>,
+	issues: &mut Vec<VarIssue>,
+) {
+	let mut push = |var: &str, problem: String| {
+		let issue = VarIssue {
+			var: var.to_string(),
+			preset: preset.map(str::to_string),
+			problem,
+		};
+
+		if !issues.contains(&issue) {
+			issues.push(issue);
+		}
+	};
+
+	for (name, declaration) in declarations {
+		let Some(value) = vars.get(name) else {
+			if declaration.required {
+				push(name, "is required but was not set".to_string());
+			}
+
+			continue;
+		};
+
+		if let Some(kind) = declaration.kind
+			&& !kind.matches(value)
+		{
+			push(
+				name,
+				format!(
+					"expected a value of type {}, found {}",
+					kind.name(),
+					value_type_name(value)
+				),
+			);
+		}
+
+		if !declaration.allowed_values.is_empty() && !declaration.allowed_values.contains(value) {
+			let allowed: Vec<String> = declaration
+				.allowed_values
+				.iter()
+				.map(|v| v.to_string())
+				.collect();
+
+			push(
+				name,
+				format!("must be one of [ {} ], found {value}", allowed.join(", ")),
+			);
+		}
+
+		if let Some(pattern) = &declaration.regex
+			&& let Value::String(string) = value
+		{
+			match Regex::new(pattern) {
+				Ok(regex) => {
+					if !regex.is_match(string) {
+						push(
+							name,
+							format!("the value `{string}` does not match the regex `{pattern}`"),
+						);
+					}
+				}
+				Err(e) => push(name, format!("the regex `{pattern}` is invalid: {e}")),
+			};
+		}
+	}
+}
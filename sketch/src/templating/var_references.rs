@@ -0,0 +1,252 @@
+use tera::ast::{Expr, ExprVal, FunctionCall, LogicOperator, Node};
+
+use super::*;
+
+/// Collects the names of the context variables that are used in a template (and in the templates that it extends or includes).
+///
+/// Variables that can be safely left undefined (like those used in `if` conditions, in `is defined` tests or with the `default` filter) are not collected.
+pub(crate) fn referenced_vars(tera: &Tera, template_name: &str) -> IndexSet<String> {
+	let mut collector = VarCollector::default();
+
+	collector.visit_template(tera, template_name);
+
+	let VarCollector {
+		mut vars,
+		local_vars,
+		..
+	} = collector;
+
+	vars.retain(|var| !local_vars.contains(var));
+
+	vars
+}
+
+#[derive(Default)]
+struct VarCollector {
+	vars: IndexSet<String>,
+	// Vars defined inside the template, with `set` or in for loops
+	local_vars: IndexSet<String>,
+	visited_templates: IndexSet<String>,
+	// Vars that are checked by the `if` blocks that are being visited
+	guarded_vars: Vec<String>,
+}
+
+impl VarCollector {
+	fn visit_template(&mut self, tera: &Tera, name: &str) {
+		if !self.visited_templates.insert(name.to_string()) {
+			return;
+		}
+
+		if let Ok(template) = tera.get_template(name) {
+			self.visit_nodes(tera, &template.ast);
+		}
+	}
+
+	fn visit_nodes(&mut self, tera: &Tera, nodes: &[Node]) {
+		for node in nodes {
+			match node {
+				Node::VariableBlock(_, expr) => self.visit_expr(expr),
+				Node::Set(_, set) => {
+					self.local_vars.insert(set.key.clone());
+					self.visit_expr(&set.value);
+				}
+				Node::FilterSection(_, section, _) => {
+					self.visit_function_call(&section.filter);
+					self.visit_nodes(tera, &section.body);
+				}
+				Node::Block(_, block, _) => self.visit_nodes(tera, &block.body),
+				Node::Forloop(_, forloop, _) => {
+					if let Some(key) = &forloop.key {
+						self.local_vars.insert(key.clone());
+					}
+					self.local_vars.insert(forloop.value.clone());
+
+					self.visit_expr(&forloop.container);
+					self.visit_nodes(tera, &forloop.body);
+
+					if let Some(empty_body) = &forloop.empty_body {
+						self.visit_nodes(tera, empty_body);
+					}
+				}
+				Node::If(if_node, _) => {
+					for (_, condition, body) in &if_node.conditions {
+						self.visit_condition(condition);
+
+						let guards_count = self.guarded_vars.len();
+						collect_guards(condition, &mut self.guarded_vars);
+
+						self.visit_nodes(tera, body);
+
+						self.guarded_vars.truncate(guards_count);
+					}
+
+					if let Some((_, body)) = &if_node.otherwise {
+						self.visit_nodes(tera, body);
+					}
+				}
+				Node::Extends(_, parent) => self.visit_template(tera, parent),
+				Node::Include(_, templates, _) => {
+					for template in templates {
+						self.visit_template(tera, template);
+					}
+				}
+				// Macros have their own scope
+				_ => {}
+			}
+		}
+	}
+
+	// Undefined vars are falsy in conditions
+	fn visit_condition(&mut self, expr: &Expr) {
+		match &expr.val {
+			ExprVal::Ident(_) if expr.filters.is_empty() => {}
+			ExprVal::Logic(logic)
+				if matches!(logic.operator, LogicOperator::And | LogicOperator::Or) =>
+			{
+				self.visit_condition(&logic.lhs);
+				self.visit_condition(&logic.rhs);
+			}
+			_ => self.visit_expr(expr),
+		}
+	}
+
+	fn visit_expr(&mut self, expr: &Expr) {
+		for filter in &expr.filters {
+			self.visit_function_call(filter);
+		}
+
+		if expr
+			.filters
+			.iter()
+			.any(|filter| filter.name == "default")
+		{
+			return;
+		}
+
+		self.visit_expr_val(&expr.val);
+	}
+
+	fn visit_expr_val(&mut self, val: &ExprVal) {
+		match val {
+			ExprVal::Ident(ident) => {
+				let root = ident.split(['.', '[']).next().unwrap_or(ident);
+
+				if root != "loop"
+					&& root != "__tera_context"
+					&& !self.guarded_vars.iter().any(|var| var == root)
+				{
+					self.vars.insert(root.to_string());
+				}
+			}
+			ExprVal::Math(math) => {
+				self.visit_expr(&math.lhs);
+				self.visit_expr(&math.rhs);
+			}
+			ExprVal::Logic(logic) => {
+				self.visit_expr(&logic.lhs);
+				self.visit_expr(&logic.rhs);
+			}
+			// The tested ident is allowed to be undefined
+			ExprVal::Test(test) => {
+				for arg in &test.args {
+					self.visit_expr(arg);
+				}
+			}
+			ExprVal::MacroCall(call) => {
+				for arg in call.args.values() {
+					self.visit_expr(arg);
+				}
+			}
+			ExprVal::FunctionCall(call) => self.visit_function_call(call),
+			ExprVal::Array(items) => {
+				for item in items {
+					self.visit_expr(item);
+				}
+			}
+			ExprVal::StringConcat(concat) => {
+				for value in &concat.values {
+					self.visit_expr_val(value);
+				}
+			}
+			ExprVal::In(in_expr) => {
+				self.visit_expr(&in_expr.lhs);
+				self.visit_expr(&in_expr.rhs);
+			}
+			ExprVal::String(_) | ExprVal::Int(_) | ExprVal::Float(_) | ExprVal::Bool(_) => {}
+		}
+	}
+
+	fn visit_function_call(&mut self, call: &FunctionCall) {
+		for arg in call.args.values() {
+			self.visit_expr(arg);
+		}
+	}
+}
+
+fn root_ident(ident: &str) -> &str {
+	ident.split(['.', '[']).next().unwrap_or(ident)
+}
+
+// Collects the vars that must be defined for the body of an `if` block to be rendered
+fn collect_guards(condition: &Expr, guards: &mut Vec<String>) {
+	if condition.negated || !condition.filters.is_empty() {
+		return;
+	}
+
+	match &condition.val {
+		ExprVal::Ident(ident) => guards.push(root_ident(ident).to_string()),
+		ExprVal::Test(test) if test.name == "defined" && !test.negated => {
+			guards.push(root_ident(&test.ident).to_string());
+		}
+		ExprVal::Logic(logic) if matches!(logic.operator, LogicOperator::And) => {
+			collect_guards(&logic.lhs, guards);
+			collect_guards(&logic.rhs, guards);
+		}
+		_ => {}
+	}
+}
+
+/// Collects the variables used by the templates of a preset. Remote templates are not included, since they are only loaded when they are rendered.
+pub(crate) fn preset_referenced_vars(
+	tera: &mut Tera,
+	preset: &TemplatingPreset,
+) -> AppResult<IndexSet<String>> {
+	let mut vars = IndexSet::new();
+
+	for template in &preset.templates {
+		match template {
+			TemplateKind::Single(TemplateData { template, .. }) => {
+				if let TemplateRef::Inline { name, content } = template {
+					tera.add_raw_template(name, content)
+						.map_err(|e| AppError::TemplateParsing {
+							template: name.clone(),
+							source: e,
+						})?;
+				}
+
+				vars.extend(referenced_vars(tera, template.name()));
+			}
+			TemplateKind::Structured(StructuredPreset { dir, exclude }) => {
+				let exclude_glob = build_globset(exclude)?;
+
+				let template_names: Vec<String> = tera
+					.get_template_names()
+					.filter(|name| {
+						Path::new(name).starts_with(dir)
+							&& !exclude_glob
+								.as_ref()
+								.is_some_and(|globset| globset.is_match(name))
+					})
+					.map(str::to_string)
+					.collect();
+
+				for name in template_names {
+					vars.extend(referenced_vars(tera, &name));
+				}
+			}
+			TemplateKind::Remote(_) => {}
+		};
+	}
+
+	Ok(vars)
+}
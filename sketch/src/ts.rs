@@ -77,9 +77,8 @@ impl TypescriptConfig {
 		Ok(self
 			.vitest_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::Vitest,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::Vitest, id, self.vitest_presets.keys())
 			})?
 			.clone())
 	}
@@ -87,9 +86,8 @@ impl TypescriptConfig {
 	pub fn get_tsconfig_preset(&self, id: &str) -> AppResult<TsConfigPreset> {
 		self.ts_config_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::TsConfig,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::TsConfig, id, self.ts_config_presets.keys())
 			})?
 			.clone()
 			.merge_presets(id, &self.ts_config_presets)
@@ -99,9 +97,8 @@ impl TypescriptConfig {
 		Ok(self
 			.package_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::TsPackage,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::TsPackage, id, self.package_presets.keys())
 			})?
 			.clone())
 	}
@@ -109,9 +106,8 @@ impl TypescriptConfig {
 	pub fn get_pnpm_preset(&self, id: &str) -> AppResult<PnpmPreset> {
 		self.pnpm_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::PnpmWorkspace,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::PnpmWorkspace, id, self.pnpm_presets.keys())
 			})?
 			.clone()
 			.merge_presets(id, &self.pnpm_presets)
@@ -120,9 +116,8 @@ impl TypescriptConfig {
 	pub fn get_oxlint_preset(&self, id: &str) -> AppResult<OxlintPreset> {
 		self.oxlint_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::Oxlint,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(PresetKind::Oxlint, id, self.oxlint_presets.keys())
 			})?
 			.clone()
 			.merge_presets(id, &self.oxlint_presets)
@@ -131,9 +126,12 @@ impl TypescriptConfig {
 	pub fn get_package_json(&self, id: &str) -> AppResult<PackageJsonPreset> {
 		self.package_json_presets
 			.get(id)
-			.ok_or_else(|| AppError::PresetNotFound {
-				kind: PresetKind::PackageJson,
-				name: id.to_string(),
+			.ok_or_else(|| {
+				AppError::preset_not_found(
+					PresetKind::PackageJson,
+					id,
+					self.package_json_presets.keys(),
+				)
 			})?
 			.clone()
 			.merge_presets(id, &self.package_json_presets)
@@ -22,6 +22,10 @@ impl ExtensiblePreset for OxlintPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 impl Default for OxlintPresetRef {
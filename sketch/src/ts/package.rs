@@ -42,6 +42,10 @@ pub struct TsPackagePreset {
 	/// The configuration for this package's oxlint setup. It can be set to `true` (to use defaults), to a preset id, or to a literal configuration.
 	#[arg(long, value_name = "ID")]
 	pub oxlint: Option<OxlintPresetRef>,
+
+	/// The policy for the files generated by this preset that already exist. It overrides the global policy, but not the `overwrite_rules`.
+	#[arg(skip)]
+	pub overwrite: Option<OverwritePolicy>,
 }
 
 /// The kinds of Ts package data. Either an id pointing to a stored preset, or a custom configuration.
@@ -72,11 +76,18 @@ pub struct TsPackageSetup<'a> {
 	pub cli_vars: &'a IndexMap<String, Value>,
 	pub package_type: PackageType,
 	pub install: bool,
+	pub manifest: &'a mut GenerationManifest,
 }
 
 impl Config {
 	/// Generates a new typescript package.
-	pub async fn create_ts_package(mut self, setup: TsPackageSetup<'_>) -> Result<(), AppError> {
+	///
+	/// If any of the steps fails, the files and directories created up to that point are removed, and the files that were overwritten are restored.
+	pub async fn create_ts_package(self, setup: TsPackageSetup<'_>) -> Result<(), AppError> {
+		with_written_files(with_transaction(Box::pin(self.generate_ts_package(setup)))).await
+	}
+
+	async fn generate_ts_package(mut self, setup: TsPackageSetup<'_>) -> Result<(), AppError> {
 		let TsPackageSetup {
 			data,
 			pkg_root,
@@ -84,6 +95,7 @@ impl Config {
 			cli_vars,
 			mut package_type,
 			install,
+			manifest,
 		} = setup;
 
 		let overwrite = self.can_overwrite();
@@ -98,9 +110,12 @@ impl Config {
 			TsPackagePresetRef::PresetId(id) => typescript
 				.package_presets
 				.get(&id)
-				.ok_or_else(|| AppError::PresetNotFound {
-					kind: PresetKind::TsPackage,
-					name: id.clone(),
+				.ok_or_else(|| {
+					AppError::preset_not_found(
+						PresetKind::TsPackage,
+						&id,
+						typescript.package_presets.keys(),
+					)
 				})?
 				.clone(),
 		};
@@ -183,11 +198,11 @@ impl Config {
 			}
 		}
 
-		serialize_json(
-			&package_json_data,
-			&pkg_root.join("package.json"),
-			overwrite,
-		)?;
+		let package_json_path = pkg_root.join("package.json");
+
+		serialize_json(&package_json_data, &package_json_path, overwrite)?;
+
+		manifest.record_main_file(&package_json_path)?;
 
 		if let PackageType::MonorepoRoot { pnpm } = &mut package_type
 			&& let Some(pnpm_data) = pnpm
@@ -199,7 +214,11 @@ impl Config {
 			#[cfg(feature = "npm-version")]
 			pnpm::add_deps_to_catalog(pnpm_data, version_ranges, &package_json_data).await?;
 
-			serialize_yaml(&pnpm_data, &pkg_root.join("pnpm-workspace.yaml"), overwrite)?;
+			let pnpm_workspace_path = pkg_root.join("pnpm-workspace.yaml");
+
+			serialize_yaml(&pnpm_data, &pnpm_workspace_path, overwrite)?;
+
+			manifest.record_main_file(&pnpm_workspace_path)?;
 		}
 
 		#[cfg(feature = "npm-version")]
@@ -223,17 +242,21 @@ impl Config {
 			serialize_yaml(&pnpm_workspace, &pnpm_workspace_path, overwrite)?;
 		}
 
-		let mut tsconfig_files: Vec<(String, TsConfig)> = Default::default();
+		// The id of the preset is only set for the files that come from a tsconfig preset
+		let mut tsconfig_files: Vec<(String, TsConfig, Option<String>)> = Default::default();
 
 		if !package_config.ts_config.is_empty() {
 			for directive in package_config.ts_config {
-				let tsconfig_data = match directive.config.unwrap_or_default() {
-					TsConfigPresetRef::PresetId(id) => typescript.get_tsconfig_preset(&id)?.config,
-					TsConfigPresetRef::Preset(ts_config) => {
+				let (tsconfig_data, preset_id) = match directive.config.unwrap_or_default() {
+					TsConfigPresetRef::PresetId(id) => {
+						(typescript.get_tsconfig_preset(&id)?.config, id)
+					}
+					TsConfigPresetRef::Preset(ts_config) => (
 						ts_config
 							.merge_presets("__inlined", &typescript.ts_config_presets)?
-							.config
-					}
+							.config,
+						"__inlined".to_string(),
+					),
 				};
 
 				tsconfig_files.push((
@@ -241,6 +264,7 @@ impl Config {
 						.output
 						.unwrap_or_else(|| "tsconfig.json".to_string()),
 					tsconfig_data,
+					Some(preset_id),
 				));
 			}
 		} else if package_type.is_monorepo_root() {
@@ -253,17 +277,29 @@ impl Config {
 				..Default::default()
 			};
 
-			tsconfig_files.push(("tsconfig.json".to_string(), root_tsconfig_plain));
+			tsconfig_files.push(("tsconfig.json".to_string(), root_tsconfig_plain, None));
 
 			let tsconfig_options = get_default_root_tsconfig();
 
-			tsconfig_files.push((root_tsconfig_name, tsconfig_options));
+			tsconfig_files.push((root_tsconfig_name, tsconfig_options, None));
 		} else {
-			tsconfig_files.push(("tsconfig.json".to_string(), get_default_package_tsconfig()));
+			tsconfig_files.push((
+				"tsconfig.json".to_string(),
+				get_default_package_tsconfig(),
+				None,
+			));
 		}
 
-		for (file, tsconfig) in tsconfig_files {
-			serialize_json(&tsconfig, &pkg_root.join(file), overwrite)?;
+		for (file, tsconfig, preset_id) in tsconfig_files {
+			let tsconfig_path = pkg_root.join(file);
+
+			serialize_json(&tsconfig, &tsconfig_path, overwrite)?;
+
+			if preset_id.is_some() {
+				manifest.record_file(&tsconfig_path, PresetKind::TsConfig, preset_id.as_deref())?;
+			} else {
+				manifest.record_main_file(&tsconfig_path)?;
+			}
 		}
 
 		for path in tsconfig_files_to_update {
@@ -287,10 +323,10 @@ impl Config {
 		if let Some(vitest_config) = package_config.vitest
 			&& vitest_config.is_enabled()
 		{
-			let mut vitest = match vitest_config {
-				VitestPresetRef::Bool(_) => VitestPreset::default(),
-				VitestPresetRef::PresetId(id) => typescript.get_vitest_preset(&id)?,
-				VitestPresetRef::Preset(preset) => preset,
+			let (mut vitest, vitest_id) = match vitest_config {
+				VitestPresetRef::Bool(_) => (VitestPreset::default(), None),
+				VitestPresetRef::PresetId(id) => (typescript.get_vitest_preset(&id)?, Some(id)),
+				VitestPresetRef::Preset(preset) => (preset, Some("__inlined".to_string())),
 			};
 
 			let tests_dir = pkg_root.join(&vitest.tests_dir);
@@ -329,38 +365,64 @@ impl Config {
 			let test_setup_file = read_to_string(templates_dir().join("ts/tests_setup.ts.j2"))
 				.context("Failed to read tests setup template")?;
 
-			write_file(
-				&tests_setup_dir.join("tests_setup.ts"),
-				&test_setup_file,
-				true,
-			)?;
+			let test_setup_path = tests_setup_dir.join("tests_setup.ts");
+
+			write_file(&test_setup_path, &test_setup_file, true)?;
+
+			for path in [&file_path, &test_setup_path] {
+				if vitest_id.is_some() {
+					manifest.record_file(path, PresetKind::Vitest, vitest_id.as_deref())?;
+				} else {
+					manifest.record_main_file(path)?;
+				}
+			}
 		}
 
 		if let Some(oxlint_config) = package_config.oxlint
 			&& oxlint_config.is_enabled()
 		{
-			let oxlint_config = match oxlint_config {
-				OxlintPresetRef::Bool(_) => OxlintConfig::default(),
-				OxlintPresetRef::PresetId(id) => typescript.get_oxlint_preset(&id)?.config,
-				OxlintPresetRef::Preset(oxlint_preset) => {
+			let (oxlint_config, oxlint_id) = match oxlint_config {
+				OxlintPresetRef::Bool(_) => (OxlintConfig::default(), None),
+				OxlintPresetRef::PresetId(id) => {
+					(typescript.get_oxlint_preset(&id)?.config, Some(id))
+				}
+				OxlintPresetRef::Preset(oxlint_preset) => (
 					oxlint_preset
 						.merge_presets(
 							&format!("__inlined_definition_{package_name}"),
 							&typescript.oxlint_presets,
 						)?
-						.config
-				}
+						.config,
+					Some("__inlined".to_string()),
+				),
 			};
 
-			serialize_json(&oxlint_config, &pkg_root.join(".oxlintrc.json"), overwrite)?;
+			let oxlint_path = pkg_root.join(".oxlintrc.json");
+
+			serialize_json(&oxlint_config, &oxlint_path, overwrite)?;
+
+			if oxlint_id.is_some() {
+				manifest.record_file(&oxlint_path, PresetKind::Oxlint, oxlint_id.as_deref())?;
+			} else {
+				manifest.record_main_file(&oxlint_path)?;
+			}
 		}
 
 		if let Some(license) = package_config.license {
-			write_file(&pkg_root.join("LICENSE"), license.get_content(), overwrite)?;
+			let license_path = pkg_root.join("LICENSE");
+
+			write_file(&license_path, license.get_content(), overwrite)?;
+
+			manifest.record_main_file(&license_path)?;
 		}
 
 		if !package_config.with_templates.is_empty() {
-			self.generate_templates(&pkg_root, package_config.with_templates, cli_vars)?;
+			self.render_templating_presets(
+				&pkg_root,
+				package_config.with_templates,
+				cli_vars,
+				Some(manifest),
+			)?;
 		}
 
 		if install {
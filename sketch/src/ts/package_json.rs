@@ -9,6 +9,10 @@ impl ExtensiblePreset for PackageJsonPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 /// A [`PackageJson`] preset.
@@ -23,6 +23,10 @@ impl ExtensiblePreset for PnpmPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 #[cfg(feature = "npm-version")]
@@ -23,6 +23,10 @@ impl ExtensiblePreset for TsConfigPreset {
 	fn extended_ids(&mut self) -> &mut IndexSet<String> {
 		&mut self.extends_presets
 	}
+
+	fn extended_preset_ids(&self) -> &IndexSet<String> {
+		&self.extends_presets
+	}
 }
 
 /// The kind of data for a [`TsConfig`]. It can be a string indicating a preset it, or a full configuration.
@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
+use indexmap::IndexMap;
 use sketch_it::{
 	AppError,
 	config::Config,
+	manifest::{GenerationCommand, GenerationManifest},
 	ts::package::{PackageType, TsPackagePresetRef, TsPackageSetup},
 };
 
@@ -26,14 +28,25 @@ async fn circular_configs() -> Result<(), AppError> {
 async fn circular_package_json() -> Result<(), AppError> {
 	let config = Config::from_file(PathBuf::from("tests/circular_package_json/sketch.toml"))?;
 
+	let pkg_root = PathBuf::from("tests/output/circular_configs");
+	let mut manifest = GenerationManifest::new(
+		&pkg_root,
+		GenerationCommand::TsPackage {
+			preset: None,
+			vitest: None,
+		},
+		&IndexMap::new(),
+	);
+
 	let result = config
 		.create_ts_package(TsPackageSetup {
 			data: TsPackagePresetRef::PresetId("circular_package_json".to_string()),
-			pkg_root: &PathBuf::from("tests/output/circular_configs"),
+			pkg_root: &pkg_root,
 			tsconfig_files_to_update: vec![],
 			cli_vars: &Default::default(),
 			package_type: PackageType::Normal,
 			install: false,
+			manifest: &mut manifest,
 		})
 		.await;
 
@@ -53,14 +66,25 @@ async fn circular_package_json() -> Result<(), AppError> {
 async fn circular_tsconfig() -> Result<(), AppError> {
 	let config = Config::from_file(PathBuf::from("tests/circular_tsconfigs/sketch.toml"))?;
 
+	let pkg_root = PathBuf::from("tests/output/circular_configs");
+	let mut manifest = GenerationManifest::new(
+		&pkg_root,
+		GenerationCommand::TsPackage {
+			preset: None,
+			vitest: None,
+		},
+		&IndexMap::new(),
+	);
+
 	let result = config
 		.create_ts_package(TsPackageSetup {
 			data: TsPackagePresetRef::PresetId("circular_tsconfigs".to_string()),
-			pkg_root: &PathBuf::from("tests/output/circular_configs"),
+			pkg_root: &pkg_root,
 			tsconfig_files_to_update: vec![],
 			cli_vars: &Default::default(),
 			package_type: PackageType::Normal,
 			install: false,
+			manifest: &mut manifest,
 		})
 		.await;
 